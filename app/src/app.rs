@@ -15,6 +15,7 @@ use crate::app::mode::SubScreens;
 use crate::app::popup::{handle_display_popup, popup_creator_raw};
 use crate::app::storage::FileStore;
 use crate::fixturestore::FixtureStore;
+use crate::fixturestore::bundle::{BundleError, ConflictResolution, FixtureBundle};
 use crate::get_runtime;
 
 mod common_data;
@@ -23,6 +24,8 @@ mod mode;
 mod storage;
 mod popup;
 mod debug;
+mod upgrade;
+mod programmer;
 
 const LAST_OPENED_FILE: &str = "LAST_OPENED_FILE";
 const APP:&str = "app";
@@ -43,12 +46,16 @@ pub struct App{
 enum FileDialog{
     ProjectSaveNew,
     ProjectOpen,
+    FixtureExport(FixtureBundle),
+    FixtureImport(ConflictResolution),
 }
 impl Display for FileDialog {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ProjectSaveNew => write!(f, "Save New"),
             Self::ProjectOpen => write!(f, "Open"),
+            Self::FixtureExport(_) => write!(f, "Export Fixtures"),
+            Self::FixtureImport(_) => write!(f, "Import Fixtures"),
         }
     }
 }
@@ -169,6 +176,8 @@ impl App {
                                     }
                                 }
                             }
+                            FileDialog::FixtureExport(bundle) => self.export_fixture_bundle(path, bundle),
+                            FileDialog::FixtureImport(resolution) => self.import_fixture_bundle(path, resolution),
                         }
                     },
                     Ok(None) => {
@@ -196,6 +205,113 @@ impl App {
             }
         }
     }
+    fn export_fixture_bundle(&self, path: Arc<Path>, bundle: FixtureBundle) {
+        let popups = self.other_app_state.popups.clone();
+        tokio::spawn(async move {
+            let result = match bundle.to_ron() {
+                Ok(data) => tokio::fs::write(&path, data).await.map_err(BundleError::from),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(()) => {
+                    let count = bundle.get_fixtures().len();
+                    log::info!("Exported {count} fixtures to {:?}", path.as_ref());
+                    popup::popup_creator(
+                        popups,
+                        "Exported Fixtures",
+                        move |_, ui|{
+                            ui.label(format!("{count} Fixture(s) have been exported."));
+                        }
+                    );
+                },
+                Err(err) => {
+                    log::warn!("Could not export fixtures to {:?}: {}", path.as_ref(), &err);
+                    popup::handle_display_popup_arc(
+                        &popups,
+                        "Couldn't export the Fixtures to the selected Location.",
+                        &err,
+                        "Couldn't export Fixtures"
+                    )
+                }
+            }
+        });
+    }
+
+    fn import_fixture_bundle(&self, path: Arc<Path>, resolution: ConflictResolution) {
+        let popups = self.other_app_state.popups.clone();
+        tokio::spawn(async move {
+            let bundle = tokio::fs::read_to_string(&path).await
+                .map_err(BundleError::from)
+                .and_then(|data|FixtureBundle::from_ron(data.as_str()));
+            match bundle {
+                Err(err) => {
+                    log::warn!("Could not import fixtures from {:?}: {}", path.as_ref(), &err);
+                    popup::handle_display_popup_arc(
+                        &popups,
+                        "Couldn't import the Fixtures from the selected File.",
+                        &err,
+                        "Couldn't import Fixtures"
+                    )
+                },
+                Ok(bundle) => {
+                    //the store can only be changed from the ui thread, so the import is done by a popup, that closes itself immediately
+                    let mut bundle = Some(bundle);
+                    popups.lock().await.push_back(Box::new(move |app: &mut Self, _: &egui::Context, _: &mut eframe::Frame|{
+                        if let Some(bundle) = bundle.take() {
+                            app.add_fixture_bundle(&path, bundle, resolution);
+                        }
+                        false
+                    }));
+                },
+            }
+        });
+    }
+
+    fn add_fixture_bundle(&mut self, path: &Path, bundle: FixtureBundle, resolution: ConflictResolution) {
+        let report = self.serializable_app_data.fixture_store.import_bundle(bundle, resolution);
+        log::info!("Imported fixtures from {:?}: {:?}", path, &report);
+        let outdated = upgrade::find_outdated_devices(&self.serializable_app_data.fixture_store, &self.serializable_app_data.data.devices);
+        if !outdated.is_empty() {
+            let popups = self.other_app_state.popups.clone();
+            let popup = upgrade::upgrade_popup(outdated);
+            tokio::spawn(async move {
+                popups.lock().await.push_back(popup)
+            });
+        }
+        popup::popup_creator(
+            self.other_app_state.popups.clone(),
+            "Imported Fixtures",
+            move |_, ui|{
+                ui.label(format!("Added: {}", report.added));
+                ui.label(format!("Overwritten: {}", report.overwritten));
+                ui.label(format!("Skipped: {}", report.skipped));
+                for (old, new) in &report.renamed {
+                    ui.label(format!("\"{old}\" was imported as \"{new}\""));
+                }
+                if !report.lints.is_empty() {
+                    ui.separator();
+                    ui.label("The imported fixtures have the following problems:");
+                    for (model, lint) in &report.lints {
+                        ui.label(format!("{}: {model}: {lint}", lint.severity()));
+                    }
+                }
+            }
+        );
+    }
+
+    ///Loads the fixture store of the project at `path` together with the built-in fixtures.
+    ///This is used by `--lint-fixtures`, which runs without the ui.
+    pub fn load_fixture_store(path: Arc<Path>) -> Result<FixtureStore, String> {
+        let (err, file_store) = get_runtime().block_on(FileStore::from_ron_filepath(path));
+        if let Some(err) = err {
+            return Err(err.to_string());
+        }
+        let app_data = file_store.get_string(APP).ok_or_else(||"The project contains no app data.".to_string())?;
+        let mut app: Self = ron::de::from_str(app_data.as_str()).map_err(|err|err.to_string())?;
+        app.serializable_app_data.fixture_store.populate_fixture_store_defaults();
+        Ok(app.serializable_app_data.fixture_store)
+    }
+
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // This is also where you can customize the look and feel of egui using
@@ -263,6 +379,12 @@ impl App {
         }
         let mut slf = app.unwrap_or_default();
         slf.serializable_app_data.fixture_store.populate_fixture_store_defaults();
+        slf.serializable_app_data.data.migrate();
+        slf.serializable_app_data.common_data_copy.migrate();
+        let outdated = upgrade::find_outdated_devices(&slf.serializable_app_data.fixture_store, &slf.serializable_app_data.data.devices);
+        if !outdated.is_empty() {
+            popups.push_back(upgrade::upgrade_popup(outdated));
+        }
         slf.other_app_state.file_store = Arc::new(RwLock::new(file_store));
         slf.other_app_state.project_file = last_opened_file_opt;
         slf.other_app_state.common_data_mutex = Arc::new(RwLock::new(slf.serializable_app_data.common_data_copy.clone()));
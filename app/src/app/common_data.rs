@@ -1,4 +1,8 @@
+use std::collections::BTreeMap;
 use serde_derive::{Deserialize, Serialize};
+use crate::artnet::fixture::DeviceId;
+use crate::artnet::group::DeviceGroup;
+use crate::artnet::mixer;
 use crate::artnet::universe::{UniverseChannels, UniverseDevices, Universes};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq)]
@@ -7,6 +11,11 @@ pub struct CommonData{
     pub devices: Universes<UniverseDevices>,
     pub overrides: Universes<UniverseMasteredChannel<Option<u8>>>,
     pub global_multiplier: u8,
+    #[serde(default)]
+    pub groups: Vec<DeviceGroup>,
+    ///Submasters, that scale the intensity channels of single devices. Devices without an entry are at full.
+    #[serde(default)]
+    pub device_masters: BTreeMap<DeviceId, u8>,
 }
 
 impl Default for CommonData{
@@ -15,14 +24,53 @@ impl Default for CommonData{
             devices: Universes::default(),
             overrides: Universes::default(),
             global_multiplier: u8::MAX,
+            groups: Vec::new(),
+            device_masters: BTreeMap::new(),
         }
     }
 }
 
+impl CommonData {
+    ///Upgrades data loaded from older projects. This should be called after deserializing.
+    pub fn migrate(&mut self) {
+        self.devices.assign_device_ids();
+    }
+
+    ///The submaster of the device with `id`.
+    #[inline]
+    pub fn device_master(&self, id: DeviceId) -> u8 {
+        self.device_masters.get(&id).copied().unwrap_or(u8::MAX)
+    }
+
+    ///The combined submaster of the device with `id` and of all groups, that contain it.
+    pub fn effective_master(&self, id: DeviceId) -> u8 {
+        self.groups.iter()
+            .filter(|group|group.contains(id))
+            .fold(self.device_master(id), |master, group|mixer::scale(master, group.master))
+    }
+
+    ///Computes the dmx values, that get sent for `universe`. See [`mixer::mix_universe`].
+    pub fn mix_universe(&self, universe: ux2::u15) -> UniverseChannels<u8> {
+        let universe = <ux2::u15 as Into<usize>>::into(universe);
+        let overrides = self.overrides.get(universe);
+        mixer::mix_universe(
+            self.devices.get(universe),
+            overrides.map(|overrides|&overrides.channels),
+            mixer::scale(self.global_multiplier, overrides.map_or(u8::MAX, |overrides|overrides.multiplier)),
+            overrides.is_some_and(|overrides|overrides.scale_unpatched),
+            |id|self.effective_master(id),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq)]
 #[non_exhaustive]
 pub struct UniverseMasteredChannel<T>{
+    ///Scales the intensity channels of all devices in this universe.
     pub multiplier: u8,
+    ///If set, the multipliers also scale all channels, that don't belong to a device.
+    #[serde(default)]
+    pub scale_unpatched: bool,
     pub channels: UniverseChannels<T>
 }
 
@@ -30,6 +78,7 @@ impl<T:Default> Default for UniverseMasteredChannel<T> {
     fn default() -> Self {
         UniverseMasteredChannel {
             multiplier: u8::MAX,
+            scale_unpatched: false,
             channels: UniverseChannels::default(),
         }
     }
@@ -3,9 +3,13 @@ use serde_derive::{Deserialize, Serialize};
 use crate::app::{mode, OtherAppState, SerializableAppData, SubMenu};
 
 mod fixtures;
+mod fixture_builder;
 mod todo;
 mod channels;
 mod settings;
+mod programmer;
+mod xy_pad;
+mod curve_editor;
 
 #[derive(Default, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub(super) enum AppMode{
@@ -13,6 +17,7 @@ pub(super) enum AppMode{
     #[default]
     Fixtures,
     Channels,
+    Programmer,
     Functions,
     Settings
 }
@@ -22,6 +27,7 @@ impl Display for AppMode{
             Self::FixtureBuilder => write!(f, "FixtureBuilder"),
             Self::Fixtures => write!(f, "Fixtures"),
             Self::Channels => write!(f, "Channels"),
+            Self::Programmer => write!(f, "Programmer"),
             Self::Functions => write!(f, "Functions"),
             Self::Settings => write!(f, "Settings"),
         }
@@ -30,15 +36,19 @@ impl Display for AppMode{
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub(super) struct SubScreens {
+    #[serde(default)]
+    fixture_builder: fixture_builder::FixtureBuilder,
     fixtures: fixtures::Fixtures,
     channels: channels::Channels,
+    #[serde(default)]
+    programmer: programmer::Programmer,
     settings: settings::Settings,
 }
 
 impl SubScreens {
     pub(super) fn menu_subscreen_select(ui: &mut egui::Ui, mode: &mut AppMode){
         egui::menu::menu_button(ui, "Modes", |ui|{
-            for e in [AppMode::FixtureBuilder, AppMode::Fixtures, AppMode::Channels, AppMode::Functions] {
+            for e in [AppMode::FixtureBuilder, AppMode::Fixtures, AppMode::Channels, AppMode::Programmer, AppMode::Functions] {
                 ui.selectable_value(mode, e, e.to_string());
             }
         });
@@ -49,11 +59,12 @@ impl SubMenu for SubScreens {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame, serializable_app_data: &mut SerializableAppData, other_app_state: &mut OtherAppState, mode: mode::AppMode) {
         crate::profile_scope!("submenu", mode.to_string());
         match mode {
-            AppMode::FixtureBuilder |
             AppMode::Functions
                 => todo::Todo.update(ctx, frame, serializable_app_data, other_app_state, mode),
+            AppMode::FixtureBuilder => self.fixture_builder.update(ctx, frame, serializable_app_data, other_app_state, mode),
             AppMode::Fixtures => self.fixtures.update(ctx, frame, serializable_app_data, other_app_state, mode),
             AppMode::Channels => self.channels.update(ctx, frame, serializable_app_data, other_app_state, mode),
+            AppMode::Programmer => self.programmer.update(ctx, frame, serializable_app_data, other_app_state, mode),
             AppMode::Settings => self.settings.update(ctx, frame, serializable_app_data, other_app_state, mode),
         }
     }
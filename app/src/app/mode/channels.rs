@@ -1,12 +1,20 @@
+use std::sync::Arc;
 use egui::{CentralPanel, Vec2, Widget, WidgetText};
 use serde_derive::{Deserialize, Serialize};
 use crate::app::{mode, OtherAppState, SerializableAppData, SubMenu};
+use crate::artnet::fixture::channel::{Action, Channel};
+
+mod device_view;
+mod range_selector;
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub(super) struct Channels{
     ///Mode Channels:
     view_by_device: bool,
     universe: ux2::u15,
+    ///If set, the view by device shows the devices of the group with this name (from all universes) instead of the universe.
+    #[serde(default)]
+    group: Option<Arc<str>>,
 }
 
 fn common_slider(value: &mut u8, ui: &mut egui::Ui) {
@@ -45,12 +53,35 @@ fn channel_slider(name: impl Into<WidgetText>, value: &mut Option<u8>, ui: &mut
 
 impl Channels {
     fn view_by_device(&mut self, serializable_app_data: &mut SerializableAppData, ui: &mut egui::Ui) {
-        let universe = serializable_app_data.data.devices.create_or_get_universe(self.universe);
-        if universe.is_empty() {
-            ui.label("There are no devices in this universe.");
-        }else {
-            ui.label("This section is under Construction!");
+        let data = &mut serializable_app_data.data;
+        let group = self.group.as_ref().and_then(|name|data.groups.iter().find(|group|group.name == *name));
+        if group.is_none() {
+            //the group has been removed or renamed
+            self.group = None;
+        }
+        let mut devices = match group {
+            Some(group) => group.resolve(&data.devices).collect::<Vec<_>>(),
+            None => {
+                let universe = <ux2::u15 as Into<usize>>::into(self.universe);
+                data.devices.get(universe)
+                    .map(|devices|devices.iter().map(|device|(universe, device)).collect())
+                    .unwrap_or_default()
+            },
+        };
+        if devices.is_empty() {
+            ui.label(if group.is_some() {"There are no devices in this group."} else {"There are no devices in this universe."});
+            return;
         }
+        devices.sort_by_key(|(universe, device)|(*universe, device.start_channel()));
+        let masters = &mut data.device_masters;
+        egui::ScrollArea::both()
+            .show(ui, |ui|{
+                for (universe, device) in devices {
+                    let Ok(universe) = ux2::u15::try_from(universe) else { continue };
+                    let overrides = &mut data.overrides.create_or_get_universe(universe).channels;
+                    ui.group(|ui|device_view::device_strip(ui, device, overrides, masters));
+                }
+            });
     }
     fn view_by_channel(&mut self, serializable_app_data: &mut SerializableAppData, ui: &mut egui::Ui) {
         ui.horizontal(|ui|{
@@ -64,12 +95,38 @@ impl Channels {
                         .cell_layout(layout)
                         .horizontal(|mut strip|{
                             strip.cell(|ui|multiplier_slider("Global\nMaster\nMultiplier", &mut serializable_app_data.data.global_multiplier, ui));
+                            let devices = serializable_app_data.data.devices.get(self.universe.into());
+                            let output = serializable_app_data.data.mix_universe(self.universe);
                             let universe = serializable_app_data.data.overrides.create_or_get_universe(self.universe);
                             let universe_override = &mut universe.multiplier;
+                            let scale_unpatched = &mut universe.scale_unpatched;
                             let channels = &mut universe.channels;
-                            strip.cell(|ui|multiplier_slider("Universe\nMaster\nOverride", universe_override, ui));
-                            for (id, channel) in channels.iter_mut().enumerate() {
-                                strip.cell(|ui|channel_slider(format!("Override\nChannel\n{}", id+1), channel, ui));
+                            strip.cell(|ui|{
+                                multiplier_slider("Universe\nMaster\nOverride", universe_override, ui);
+                                ui.checkbox(scale_unpatched, "Unpatched")
+                                    .on_hover_text("Also scale channels, that don't belong to a device. Devices only get their dimmer and color channels scaled.");
+                            });
+                            for ((id, channel), out) in channels.iter_mut().enumerate().zip(output.iter()) {
+                                let patched = devices.zip(ux2::u9::try_from(id).ok())
+                                    .and_then(|(devices, id)|devices.device_at(id));
+                                strip.cell(|ui|{
+                                    match patched {
+                                        None => channel_slider(format!("Override\nChannel\n{}", id+1), channel, ui),
+                                        Some((device, offset)) => {
+                                            let mode = device.get_mode();
+                                            let name = format!("Override\nChannel\n{}\n{}", id+1, mode.channel_name(offset).unwrap_or_default());
+                                            match mode.get_channels().get(offset).map(Channel::get_action) {
+                                                Some(Action::Selection(ranges))
+                                                    => range_selector::range_selector(name, channel, ranges, device.get_variable_selection(), ui),
+                                                _ => channel_slider(name, channel, ui),
+                                            }
+                                            ui.label(device.name.as_ref())
+                                                .on_hover_text(format!("{} ({})", device.fixture.get_model(), mode.get_name()));
+                                        }
+                                    }
+                                    ui.label(format!("Out: {out}"))
+                                        .on_hover_text("The value, that gets sent after e.g. submasters and response curves were applied");
+                                });
                             }
                         });
                     ui.allocate_at_least(Vec2::new(1.,175.), egui::Sense::click());
@@ -91,7 +148,19 @@ impl SubMenu for Channels{
                     .clamp_range(0u16..=ux2::u15::MAX.into())
                     .speed(0.1)
                     .fixed_decimals(0)
-                    .ui(ui)
+                    .ui(ui);
+
+                if self.view_by_device && !serializable_app_data.data.groups.is_empty() {
+                    ui.label("Group: ");
+                    egui::ComboBox::from_id_source("channels:group")
+                        .selected_text(self.group.as_deref().unwrap_or("None (Universe)"))
+                        .show_ui(ui, |ui|{
+                            ui.selectable_value(&mut self.group, None, "None (Universe)");
+                            for group in &serializable_app_data.data.groups {
+                                ui.selectable_value(&mut self.group, Some(group.name.clone()), group.name.as_ref());
+                            }
+                        });
+                }
             });
 
             if self.view_by_device {
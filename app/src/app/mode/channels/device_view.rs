@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use egui::Widget;
+use crate::artnet::fixture::channel::{join_u16, split_u16, Action, Channel, Color, SimpleAction};
+use crate::app::mode::xy_pad::xy_pad;
+use crate::app::programmer::{apply_to_device, position, AttributeValue};
+use crate::artnet::fixture::{Device, DeviceId};
+use crate::artnet::fixture::variables::VariableChannelAction;
+use crate::artnet::universe::UniverseChannels;
+use crate::color::{color_channel_values, is_derivable, rgb_from_channel_values};
+use crate::degree::{deg_to_microarcseconds, microarcseconds_to_deg};
+use super::{channel_slider, common_slider};
+use super::range_selector::range_selector;
+
+///Returns the offsets and roles of all color channels of `device`.
+fn color_channels(device: &Device) -> Vec<(usize, Color)> {
+    device.get_channels().iter()
+        .enumerate()
+        .filter_map(|(offset, channel)|match channel.get_action() {
+            Action::SimpleAction(SimpleAction::IntensityColor(role)) => Some((offset, *role)),
+            _ => None,
+        })
+        .collect()
+}
+
+///A color picker, that controls all color channels of a device at once.
+///The picked color gets converted to whatever color model the channels use.
+///
+///The channels are only written, when the color or the lock is changed here,
+///so that colors set elsewhere (e.g. in the programmer) are kept.
+fn color_picker(ui: &mut egui::Ui, device: &Device, overrides: &mut UniverseChannels<Option<u8>>, channels: &[(usize, Color)]) {
+    let ids = channels.iter()
+        .filter_map(|(offset, role)|device.channel_id(*offset).map(|id|(id, *role)))
+        .collect::<Vec<_>>();
+    let mut lock = ids.iter().any(|(id, _)|overrides.get(*id).is_some());
+    let current = ids.iter()
+        .filter_map(|(id, role)|(*overrides.get(*id)).map(|value|(*role, value)))
+        .collect::<Vec<_>>();
+    //the picked color cannot always be recovered from the channel values, so it is remembered separately.
+    let color_id = ui.id().with(("device_color", device.get_id()));
+    let mut rgb = rgb_from_channel_values(current.as_slice())
+        .unwrap_or_else(||ui.data_mut(|data|*data.get_temp_mut_or(color_id, [u8::MAX; 3])));
+    let mut changed = false;
+    ui.vertical(|ui|{
+        changed |= ui.checkbox(&mut lock, "Color").changed();
+        ui.add_enabled_ui(lock, |ui|{
+            changed |= egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed();
+        });
+    });
+    ui.data_mut(|data|data.insert_temp(color_id, rgb));
+    if !changed {
+        return;
+    }
+    let roles = ids.iter().map(|(_, role)|*role).collect::<Vec<_>>();
+    for ((id, _), value) in ids.iter().zip(color_channel_values(rgb, roles.as_slice())) {
+        if value.is_some() {
+            *overrides.get_mut(*id) = if lock { value } else { None };
+        }
+    }
+}
+
+///Controls the submaster of `device`, which only scales its intensity channels.
+fn master_slider(ui: &mut egui::Ui, device: &Device, masters: &mut BTreeMap<DeviceId, u8>) {
+    let mut master = masters.get(&device.get_id()).copied().unwrap_or(u8::MAX);
+    ui.vertical(|ui|{
+        ui.label("Master")
+            .on_hover_text("Scales the dimmer and color channels of this device");
+        common_slider(&mut master, ui);
+    });
+    if master == u8::MAX {
+        masters.remove(&device.get_id());
+    } else {
+        masters.insert(device.get_id(), master);
+    }
+}
+
+///Controls a dimmer channel and shows its value in percent.
+fn dimmer_slider(ui: &mut egui::Ui, value: &mut Option<u8>) {
+    ui.vertical(|ui|{
+        channel_slider("Dimmer", value, ui);
+        let percent = u16::from(value.unwrap_or_default()) * 100 / u16::from(u8::MAX);
+        ui.label(format!("{percent}%"));
+    });
+}
+
+///Controls a 16-bit attribute (e.g. a dimmer or zoom with a fine channel) with one slider, which writes both bytes.
+///`percent` additionally shows the value in percent.
+fn fine_slider(ui: &mut egui::Ui, name: String, device: &Device, offset: usize, channel: &Channel, overrides: &mut UniverseChannels<Option<u8>>, percent: bool) {
+    let Some(coarse) = device.channel_id(offset) else { return };
+    let fine = channel.get_fine().and_then(|fine|device.channel_id(usize::from(fine)));
+    let coarse_value = *overrides.get(coarse);
+    let mut lock = coarse_value.is_some();
+    let mut value = join_u16(coarse_value.unwrap_or_default(), fine.and_then(|fine|*overrides.get(fine)).unwrap_or_default());
+    ui.vertical(|ui|{
+        ui.checkbox(&mut lock, name);
+        ui.add_enabled_ui(lock, |ui|{
+            egui::Slider::new(&mut value, u16::MIN..=u16::MAX)
+                .vertical()
+                .show_value(false)
+                .handle_shape(egui::style::HandleShape::Rect {
+                    aspect_ratio: 1./2.
+                })
+                .ui(ui);
+        });
+        if percent {
+            let percent = u32::from(value) * 100 / u32::from(u16::MAX);
+            ui.label(format!("{percent}%"));
+        } else {
+            ui.label(value.to_string());
+        }
+    });
+    for (offset, dmx_value) in channel.u16_values(offset, value) {
+        if let Some(id) = device.channel_id(offset) {
+            *overrides.get_mut(id) = lock.then_some(dmx_value);
+        }
+    }
+}
+
+///Controls the coarse and (if present) fine channel of a position attribute as one raw 16-bit value.
+fn position_control(ui: &mut egui::Ui, name: String, overrides: &mut UniverseChannels<Option<u8>>, coarse: ux2::u9, fine: Option<ux2::u9>) {
+    let coarse_value = *overrides.get(coarse);
+    let fine_value = fine.and_then(|fine|*overrides.get(fine));
+    let mut lock = coarse_value.is_some() || fine_value.is_some();
+    let mut value = join_u16(coarse_value.unwrap_or_default(), fine_value.unwrap_or_default());
+    ui.vertical(|ui|{
+        ui.checkbox(&mut lock, name);
+        ui.add_enabled_ui(lock, |ui|{
+            if fine.is_some() {
+                egui::DragValue::new(&mut value).ui(ui);
+            } else {
+                let mut coarse = split_u16(value).0;
+                egui::DragValue::new(&mut coarse).ui(ui);
+                value = join_u16(coarse, 0);
+            }
+        });
+    });
+    let (coarse_value, fine_value) = split_u16(value);
+    *overrides.get_mut(coarse) = lock.then_some(coarse_value);
+    if let Some(fine) = fine {
+        *overrides.get_mut(fine) = lock.then_some(fine_value);
+    }
+}
+
+///Converts micro-arc-seconds into whole degrees for the ui.
+fn to_degrees(microarcseconds: u64) -> u32 {
+    u32::try_from(microarcseconds_to_deg(microarcseconds)).unwrap_or(u32::MAX)
+}
+
+///Controls pan and tilt of `device` in degrees with an XY pad.
+///The pad respects the pan and tilt range, that the device uses and writes both the coarse and fine channels.
+fn position_pad(ui: &mut egui::Ui, device: &Device, overrides: &mut UniverseChannels<Option<u8>>) {
+    let (pan, tilt) = position(overrides, device);
+    if pan.is_none() && tilt.is_none() {
+        return;
+    }
+    let pan_max = pan.map_or(0, |pan|to_degrees(pan.range));
+    let tilt_max = tilt.map_or(0, |tilt|to_degrees(tilt.range));
+    let mut pan_degrees = pan.and_then(|pan|pan.value).map_or(0, to_degrees);
+    let mut tilt_degrees = tilt.and_then(|tilt|tilt.value).map_or(0, to_degrees);
+    let mut lock = pan.is_some_and(|pan|pan.value.is_some()) || tilt.is_some_and(|tilt|tilt.value.is_some());
+    let mut changed = false;
+    ui.vertical(|ui|{
+        changed |= ui.checkbox(&mut lock, "Position").changed();
+        ui.add_enabled_ui(lock, |ui|{
+            changed |= xy_pad(ui, &mut pan_degrees, pan_max, &mut tilt_degrees, tilt_max).changed();
+            if pan.is_some() {
+                ui.horizontal(|ui|{
+                    ui.label("Pan: ");
+                    changed |= egui::DragValue::new(&mut pan_degrees).clamp_range(0..=pan_max).suffix("°").ui(ui).changed();
+                });
+            }
+            if tilt.is_some() {
+                ui.horizontal(|ui|{
+                    ui.label("Tilt: ");
+                    changed |= egui::DragValue::new(&mut tilt_degrees).clamp_range(0..=tilt_max).suffix("°").ui(ui).changed();
+                });
+            }
+        });
+    });
+    if !changed {
+        return;
+    }
+    if lock {
+        apply_to_device(overrides, device, AttributeValue::Pan(deg_to_microarcseconds(pan_degrees)));
+        apply_to_device(overrides, device, AttributeValue::Tilt(deg_to_microarcseconds(tilt_degrees)));
+    } else {
+        for (offset, channel) in device.get_channels().iter().enumerate() {
+            if matches!(
+                channel.get_action(),
+                Action::SimpleAction(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(_) | VariableChannelAction::PositionTilt(_)))
+            ) {
+                let fine = channel.get_fine().map(usize::from);
+                for id in core::iter::once(offset).chain(fine).filter_map(|offset|device.channel_id(offset)) {
+                    *overrides.get_mut(id) = None;
+                }
+            }
+        }
+    }
+}
+
+///Shows controls for all channels of `device`, which write into `overrides`, and for the submaster of `device`.
+///
+///Fine channels are controlled together with their coarse channel and unused channels are hidden.
+pub(super) fn device_strip(ui: &mut egui::Ui, device: &Device, overrides: &mut UniverseChannels<Option<u8>>, masters: &mut BTreeMap<DeviceId, u8>) {
+    let mode = device.get_mode();
+    ui.horizontal(|ui|{
+        ui.vertical(|ui|{
+            ui.strong(device.name.as_ref());
+            ui.label(format!("{} ({})", device.fixture.get_model(), mode.get_name()));
+            ui.label(format!("Channel {}-{}", usize::from(device.start_channel()) + 1, device.end_channel()));
+        });
+
+        if device.get_channels().iter().any(|channel|matches!(channel.get_action(), Action::SimpleAction(action) if action.is_intensity())) {
+            master_slider(ui, device, masters);
+        }
+
+        let colors = color_channels(device);
+        let picked_colors = colors.iter()
+            .filter(|(_, role)|is_derivable(*role))
+            .copied()
+            .collect::<Vec<_>>();
+        if !picked_colors.is_empty() {
+            color_picker(ui, device, overrides, picked_colors.as_slice());
+        }
+
+        position_pad(ui, device, overrides);
+
+        for (offset, channel) in mode.get_channels().iter().enumerate() {
+            if picked_colors.iter().any(|(color_offset, _)|*color_offset == offset) {
+                continue;
+            }
+            let Some(id) = device.channel_id(offset) else { continue };
+            let name = mode.channel_name(offset).unwrap_or_default();
+            match channel.get_action() {
+                Action::Fine(_) |
+                Action::SimpleAction(SimpleAction::NoOp) |
+                Action::SimpleAction(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(_) | VariableChannelAction::PositionTilt(_))) => {},
+                Action::SimpleAction(action @ (SimpleAction::IntensityMasterDimmer | SimpleAction::BeamZoom)) if channel.get_fine().is_some() =>
+                    fine_slider(ui, name, device, offset, channel, overrides, action.is_intensity()),
+                Action::SimpleAction(SimpleAction::IntensityMasterDimmer) =>
+                    dimmer_slider(ui, overrides.get_mut(id)),
+                Action::SimpleAction(SimpleAction::VariableChannelAction(_)) => {
+                    let fine = channel.get_fine().and_then(|fine|device.channel_id(usize::from(fine)));
+                    position_control(ui, name, overrides, id, fine);
+                },
+                Action::Selection(ranges) => {
+                    ui.vertical(|ui|range_selector(name, overrides.get_mut(id), ranges, device.get_variable_selection(), ui));
+                },
+                Action::SimpleAction(_) => {
+                    ui.vertical(|ui|channel_slider(name, overrides.get_mut(id), ui));
+                },
+            }
+        }
+    });
+}
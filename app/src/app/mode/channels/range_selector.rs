@@ -0,0 +1,92 @@
+use egui::{Vec2, Widget, WidgetText};
+use crate::artnet::fixture::channel::{Range, Slot};
+use crate::artnet::fixture::variables::VariableSelection;
+
+///True, if different values inside of `range` can be picked with a fine slider.
+fn has_fine_control(range: &Range) -> bool {
+    range.is_continuous() && !range.get_action().auto_conversion() && range.len() > 1
+}
+
+///Mirrors `input` for inverted ranges, so that a higher input always means more of the effect.
+const fn directed_input(range: &Range, input: u8) -> u8 {
+    if range.is_inverted() {
+        u8::MAX - input
+    } else {
+        input
+    }
+}
+
+///Returns the input of the fine slider, for which [`Range::scale_to_range`] results in `value`.
+fn fine_input(range: &Range, value: u8) -> u8 {
+    let offset = u16::from(value.saturating_sub(range.get_start()));
+    //scale_to_range computes offset = input * len / 256, so this is the smallest input producing offset
+    let input = (offset * 256).div_ceil(range.len());
+    directed_input(range, u8::try_from(input).unwrap_or(u8::MAX))
+}
+
+///The label of `range`. Continuous ranges show their values in the direction, in which the effect increases.
+fn range_label(range: &Range) -> String {
+    if !range.is_continuous() || range.len() <= 1 {
+        return range.get_name();
+    }
+    if range.is_inverted() {
+        format!("{} ({}→{})", range.get_name(), range.get_end(), range.get_start())
+    } else {
+        format!("{} ({}→{})", range.get_name(), range.get_start(), range.get_end())
+    }
+}
+
+///Like [`channel_slider`](super::channel_slider), but lets the user pick one of the named ranges of a channel
+///(e.g. strobe off/strobe, spin left/right or the slots of a GOBO wheel).
+///
+///Picking a range snaps to the value [`Range::scale_to_range`] chooses for it.
+///Continuous ranges additionally get a fine slider for choosing a value inside of them.
+pub(super) fn range_selector(name: impl Into<WidgetText>, value: &mut Option<u8>, ranges: &[Range], variable_selection: VariableSelection, ui: &mut egui::Ui) {
+    let mut channel_value = value.unwrap_or_default();
+    let mut lock = value.is_some();
+    ui.checkbox(&mut lock, name);
+    ui.add_enabled_ui(
+        lock,
+        |ui| {
+            let mut selected_range = None;
+            for range in ranges {
+                ui.horizontal(|ui|{
+                    if let Some([r, g, b]) = range.get_slot().and_then(Slot::get_color) {
+                        let (rect, _) = ui.allocate_exact_size(Vec2::splat(10.), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 0., egui::Color32::from_rgb(r, g, b));
+                    }
+                    let selected = (range.get_start()..=range.get_end()).contains(&channel_value);
+                    if selected {
+                        selected_range = selected_range.or(Some(range));
+                    }
+                    let mut response = ui.selectable_label(selected, range_label(range));
+                    if let Some(image) = range.get_slot().and_then(Slot::get_image) {
+                        response = response.on_hover_text(image.as_ref());
+                    }
+                    if range.is_inverted() {
+                        response = response.on_hover_text("Inverted: lower values increase the effect.");
+                    }
+                    if response.clicked() {
+                        channel_value = range.scale_to_range(128, variable_selection);
+                    }
+                });
+            }
+            if let Some(range) = selected_range.filter(|range|has_fine_control(range)) {
+                let mut input = fine_input(range, channel_value);
+                if egui::Slider::new(&mut input, u8::MIN..=u8::MAX)
+                    .show_value(false)
+                    .ui(ui)
+                    .on_hover_text(format!("Fine control inside of {}", range.get_name()))
+                    .changed() {
+                    channel_value = range.scale_to_range(u64::from(directed_input(range, input)), variable_selection);
+                }
+                ui.label(format!("Value: {channel_value}"));
+            }
+        }
+    );
+    if lock {
+        *value = Some(channel_value);
+    } else{
+        *value = None;
+    }
+}
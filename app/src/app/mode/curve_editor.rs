@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use egui::{Sense, Stroke, Vec2, Widget};
+use crate::artnet::fixture::curve::ResponseCurve;
+
+const PREVIEW_SIZE: f32 = 100.;
+///Amount of line segments, that the preview is drawn with.
+const PREVIEW_SEGMENTS: u32 = 32;
+///Points, that a new custom curve starts with.
+const DEFAULT_POINTS: u8 = 5;
+const MAX_POINTS: usize = 17;
+
+///Draws `curve` with the input on the horizontal and the output on the vertical axis.
+pub(super) fn curve_preview(ui: &mut egui::Ui, curve: &ResponseCurve) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(Vec2::splat(PREVIEW_SIZE), Sense::hover());
+    let visuals = ui.visuals();
+    let points = (0..=PREVIEW_SEGMENTS)
+        .map(|segment|{
+            let input = u16::try_from(segment * u32::from(u16::MAX) / PREVIEW_SEGMENTS).unwrap_or(u16::MAX);
+            let output = curve.apply_u16(input);
+            egui::pos2(
+                egui::remap_clamp(f32::from(input), 0.0..=f32::from(u16::MAX), rect.x_range()),
+                egui::remap_clamp(f32::from(output), 0.0..=f32::from(u16::MAX), rect.bottom()..=rect.top()),
+            )
+        })
+        .collect();
+    let painter = ui.painter();
+    painter.rect(rect, 0., visuals.extreme_bg_color, visuals.widgets.noninteractive.bg_stroke);
+    painter.add(egui::Shape::line(points, Stroke::new(1.5, visuals.widgets.active.fg_stroke.color)));
+    response
+}
+
+///Lets the user choose a response curve and shows a preview of it.
+///The points of custom curves can be edited one by one.
+pub(super) fn curve_editor(ui: &mut egui::Ui, id: impl std::hash::Hash, curve: &mut ResponseCurve) {
+    ui.horizontal(|ui|{
+        ui.vertical(|ui|{
+            egui::ComboBox::from_id_source(id)
+                .selected_text(curve.to_string())
+                .show_ui(ui, |ui|{
+                    for preset in ResponseCurve::PRESETS {
+                        if ui.selectable_label(*curve == preset, preset.to_string()).clicked() {
+                            *curve = preset;
+                        }
+                    }
+                    let custom = matches!(curve, ResponseCurve::Custom(_));
+                    if ui.selectable_label(custom, "Custom").clicked() && !custom {
+                        //start with the current curve, so that switching doesn't change the output yet
+                        *curve = ResponseCurve::Custom(
+                            (0..DEFAULT_POINTS)
+                                .map(|point|curve.apply(u8::try_from(u16::from(point) * u16::from(u8::MAX) / u16::from(DEFAULT_POINTS - 1)).unwrap_or(u8::MAX)))
+                                .collect()
+                        );
+                    }
+                });
+            if let ResponseCurve::Custom(points) = curve {
+                let mut edited = points.to_vec();
+                ui.horizontal_wrapped(|ui|{
+                    for point in &mut edited {
+                        egui::DragValue::new(point).ui(ui);
+                    }
+                    if edited.len() < MAX_POINTS && ui.small_button("+").on_hover_text("Adds a point at the end").clicked() {
+                        edited.push(edited.last().copied().unwrap_or(u8::MAX));
+                    }
+                    if edited.len() > 2 && ui.small_button("-").on_hover_text("Removes the last point").clicked() {
+                        edited.pop();
+                    }
+                });
+                if edited.as_slice() != points.as_ref() {
+                    *points = Arc::from(edited);
+                }
+            }
+        });
+        curve_preview(ui, curve);
+    });
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+use egui::CentralPanel;
+use serde_derive::{Deserialize, Serialize};
+use crate::app::{mode, FileDialog, OtherAppState, SerializableAppData, SubMenu};
+use crate::app::mode::curve_editor::curve_editor;
+use crate::app::popup::popup_creator;
+use crate::app::upgrade;
+use crate::artnet::fixture::curve::ResponseCurve;
+use crate::artnet::fixture::Fixture;
+use crate::artnet::fixture::validate::Lint;
+use crate::fixturestore::bundle::ConflictResolution;
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub(super) struct FixtureBuilder{
+    ///Result of the last validation of the fixture store.
+    #[serde(skip)]
+    lints: Option<Vec<(String, Lint)>>,
+    ///Path in the fixture store, that should be exported (e.g. `VRSL` or `VRSL/Standard Laser`)
+    export_path: String,
+    conflict_resolution: ConflictResolution,
+    ///Fixture, whose response curve is being edited.
+    #[serde(skip)]
+    curve_fixture: (Vec<Arc<str>>, Option<Fixture>),
+    #[serde(skip)]
+    response_curve: ResponseCurve,
+}
+
+impl FixtureBuilder{
+    fn library(&mut self, serializable_app_data: &SerializableAppData, other_app_state: &mut OtherAppState, ui: &mut egui::Ui) {
+        ui.horizontal(|ui|{
+            ui.label("Path: ");
+            ui.text_edit_singleline(&mut self.export_path)
+                .on_hover_text("A directory (e.g. \"VRSL\") or a single fixture (e.g. \"VRSL/Standard Laser\"). Leave empty to export everything.");
+            if ui.button("Export…").clicked() {
+                let path = self.export_path.split('/')
+                    .map(str::trim)
+                    .filter(|segment|!segment.is_empty())
+                    .map(Arc::from)
+                    .collect::<Vec<Arc<str>>>();
+                let bundle = serializable_app_data.fixture_store.export(path.as_slice());
+                if bundle.get_fixtures().is_empty() {
+                    popup_creator(other_app_state.popups.clone(), "No Fixtures to Export", |_, ui|{
+                        ui.label("There are no fixtures at the given path.");
+                    });
+                } else {
+                    other_app_state.project_file_dialog = Some((
+                        FileDialog::FixtureExport(bundle),
+                        tokio::spawn(
+                            rfd::AsyncFileDialog::new()
+                                .add_filter("Fixture Bundles", &["ron"])
+                                .set_file_name("fixtures.ron")
+                                .save_file()
+                        )
+                    ));
+                }
+            }
+        });
+        ui.horizontal(|ui|{
+            ui.label("On Conflict: ");
+            egui::ComboBox::from_id_source("fixture_builder:conflict_resolution")
+                .selected_text(self.conflict_resolution.to_string())
+                .show_ui(ui, |ui|{
+                    for resolution in [ConflictResolution::Skip, ConflictResolution::Overwrite, ConflictResolution::KeepBoth] {
+                        ui.selectable_value(&mut self.conflict_resolution, resolution, resolution.to_string());
+                    }
+                });
+            if ui.button("Import…").clicked() {
+                other_app_state.project_file_dialog = Some((
+                    FileDialog::FixtureImport(self.conflict_resolution),
+                    tokio::spawn(
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("Fixture Bundles", &["ron"])
+                            .pick_file()
+                    )
+                ));
+            }
+        });
+    }
+
+    fn response_curves(&mut self, serializable_app_data: &mut SerializableAppData, other_app_state: &OtherAppState, ui: &mut egui::Ui) {
+        ui.label("The response curve gets applied to all dimmer and color channels of a fixture. Devices can override it.");
+        let prev_path = self.curve_fixture.0.clone();
+        ui.horizontal(|ui|{
+            ui.menu_button(format!("{} Fixture", if self.curve_fixture.0.is_empty() {"Set"} else {"Change"}), |ui|{
+                serializable_app_data.fixture_store.build_menu(ui, &mut self.curve_fixture);
+            });
+            ui.label(self.curve_fixture.0.join("/"));
+        });
+        if prev_path != self.curve_fixture.0 {
+            self.response_curve = self.curve_fixture.1.as_ref()
+                .map(|fixture|fixture.get_response_curve().clone())
+                .unwrap_or_default();
+        }
+        let Some(fixture) = &self.curve_fixture.1 else { return };
+        curve_editor(ui, "fixture_builder:response_curve", &mut self.response_curve);
+        if ui.add_enabled(self.response_curve != *fixture.get_response_curve(), egui::Button::new("Apply")).clicked() {
+            let fixture_store = &mut serializable_app_data.fixture_store;
+            let path = fixture.get_path();
+            let model = fixture.get_model().clone();
+            for lint in fixture_store.import(fixture.clone().with_response_curve(self.response_curve.clone())) {
+                log::warn!("Fixture {}: {}: {}", model, lint.severity(), lint);
+            }
+            self.curve_fixture.1 = fixture_store.find(path.as_ref(), &model).cloned();
+            //the devices still use the old definition
+            let outdated = upgrade::find_outdated_devices(fixture_store, &serializable_app_data.data.devices);
+            if !outdated.is_empty() {
+                let popups = other_app_state.popups.clone();
+                let popup = upgrade::upgrade_popup(outdated);
+                tokio::spawn(async move {
+                    popups.lock().await.push_back(popup)
+                });
+            }
+        }
+    }
+}
+
+impl SubMenu for FixtureBuilder{
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame, serializable_app_data: &mut SerializableAppData, other_app_state: &mut OtherAppState, _: mode::AppMode) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Fixture Library");
+            self.library(serializable_app_data, other_app_state, ui);
+            ui.separator();
+
+            ui.heading("Response Curves");
+            self.response_curves(serializable_app_data, other_app_state, ui);
+            ui.separator();
+
+            ui.heading("Validation");
+            ui.label("Building fixtures is still under construction. You can already validate the fixtures in the fixture store.");
+            if ui.button("Validate Fixture Store").clicked() {
+                self.lints = Some(serializable_app_data.fixture_store.lint());
+            }
+            match &self.lints {
+                None => {},
+                Some(lints) if lints.is_empty() => {
+                    ui.label("No problems were found.");
+                },
+                Some(lints) => {
+                    egui::ScrollArea::vertical().show(ui, |ui|{
+                        egui::Grid::new("fixture_builder:lints")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui|{
+                                ui.label("Severity");
+                                ui.label("Fixture");
+                                ui.label("Problem");
+                                ui.end_row();
+                                for (path, lint) in lints {
+                                    ui.label(lint.severity().to_string());
+                                    ui.label(path.as_str());
+                                    ui.label(lint.to_string());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                }
+            }
+        });
+    }
+}
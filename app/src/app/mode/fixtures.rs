@@ -3,26 +3,502 @@ use std::sync::Arc;
 use egui::{CentralPanel, Widget};
 use serde_derive::{Deserialize, Serialize};
 use crate::app::{mode, OtherAppState, SerializableAppData, SubMenu};
+use crate::app::common_data::UniverseMasteredChannel;
+use crate::app::mode::curve_editor::{curve_editor, curve_preview};
 use crate::app::popup::{get_id, popup_creator};
-use crate::artnet::fixture::{Device, Fixture};
+use crate::artnet::fixture::{Device, DeviceError, Fixture, FixtureMode};
+use crate::artnet::fixture::calibration::PositionCalibration;
+use crate::artnet::fixture::channel::{Action, SimpleAction};
+use crate::artnet::fixture::variables::{Variable, VariableSelection};
+use crate::artnet::universe::{UniverseDevices, Universes};
+use crate::degree::format_deg;
+use crate::fixturestore::search::SearchIndex;
+
+mod groups;
+mod patch_overview;
 
 #[derive(Debug, Default, Copy, Clone, Deserialize, Serialize)]
 pub(super) struct Fixtures;
 
+///Adds a row to the surrounding grid for every channel of `fixture_mode`, that offers several ranges (e.g. the pan range of a moving head).
+///In that row the range, that the device uses, can be chosen.
+fn variable_selection_ui(ui: &mut egui::Ui, id: impl std::hash::Hash + Copy, fixture_mode: &FixtureMode, selection: &mut VariableSelection) {
+    let mut shown = Vec::new();
+    for channel in fixture_mode.get_channels().iter() {
+        let Action::SimpleAction(SimpleAction::VariableChannelAction(action)) = channel.get_action() else { continue };
+        let Variable::Selection(options, default) = action.get_variable() else { continue };
+        let name = action.to_string();
+        if shown.contains(&name) {
+            continue;
+        }
+        let default_text = format!("Default ({})", format_deg(*default));
+        let mut selected = selection.get(action);
+        ui.label(format!("{name} Range: "));
+        egui::ComboBox::from_id_source((id, name.as_str()))
+            .selected_text(selected.and_then(|index|options.get(index)).map_or_else(||default_text.clone(), |range|format_deg(*range)))
+            .show_ui(ui, |ui|{
+                ui.selectable_value(&mut selected, None, default_text.as_str());
+                for (index, range) in options.iter().enumerate() {
+                    ui.selectable_value(&mut selected, Some(index), format_deg(*range));
+                }
+            });
+        ui.end_row();
+        selection.set(action, selected);
+        shown.push(name);
+    }
+}
+
+///Adds rows to the surrounding grid for editing how the pan/tilt of a device is mounted.
+fn position_calibration_ui(ui: &mut egui::Ui, calibration: &mut PositionCalibration) {
+    ui.label("Pan: ");
+    ui.horizontal(|ui|{
+        ui.checkbox(&mut calibration.invert_pan, "Invert");
+        egui::DragValue::new(&mut calibration.pan_offset)
+            .clamp_range(-720..=720)
+            .prefix("Offset: ")
+            .suffix("°")
+            .ui(ui);
+    });
+    ui.end_row();
+
+    ui.label("Tilt: ");
+    ui.horizontal(|ui|{
+        ui.checkbox(&mut calibration.invert_tilt, "Invert");
+        egui::DragValue::new(&mut calibration.tilt_offset)
+            .clamp_range(-720..=720)
+            .prefix("Offset: ")
+            .suffix("°")
+            .ui(ui);
+    });
+    ui.end_row();
+
+    ui.label("");
+    ui.checkbox(&mut calibration.swap, "Swap Pan/Tilt");
+    ui.end_row();
+}
+
+///Shows the channel layout of a fixture mode.
+fn mode_preview(ui: &mut egui::Ui, id: impl std::hash::Hash, fixture_mode: &FixtureMode) {
+    egui::ScrollArea::vertical()
+        .id_source(egui::Id::new(&id).with("scroll"))
+        .max_height(200.)
+        .show(ui, |ui|{
+            egui::Grid::new(id)
+                .striped(true)
+                .show(ui, |ui|{
+                    for offset in 0..fixture_mode.get_channels().len() {
+                        ui.label((offset+1).to_string());
+                        ui.label(fixture_mode.channel_name(offset).unwrap_or_default());
+                        ui.weak(fixture_mode.channel_category(offset).map(|category|category.to_string()).unwrap_or_default());
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+///Shows all fixtures of `index`, that match `search`, and how many of them match.
+///Clicking a fixture selects it into `selected`.
+///
+///Returns the fixture, that is currently hovered.
+fn fixture_search_list<'a>(ui: &mut egui::Ui, id: impl std::hash::Hash, index: &'a SearchIndex, search: &str, selected: &mut (Vec<Arc<str>>, Option<Fixture>)) -> Option<&'a Fixture> {
+    let mut highlighted = None;
+    let results = index.search(search);
+    ui.weak(format!("{} of {} fixtures match", results.len(), index.fixture_count()));
+    egui::ScrollArea::vertical()
+        .id_source(id)
+        .max_height(200.)
+        .show(ui, |ui|{
+            for result in results {
+                let modes = result.fixture.get_modes().iter()
+                    .map(|mode|mode.get_name().as_ref())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let response = ui.selectable_label(
+                    selected.1.as_ref().is_some_and(|fixture|fixture == result.fixture),
+                    format!("{} ({}; {modes})", result.path.join("/"), result.fixture.get_type())
+                );
+                if response.clicked() {
+                    *selected = (result.path.to_vec(), Some(result.fixture.clone()));
+                }
+                if response.hovered() {
+                    highlighted = Some(result.fixture);
+                }
+            }
+        });
+    highlighted
+}
+
+///Moves the channel overrides of `old` to the channels of `new`, which control the same attribute.
+///The overrides of all channels of `old` get cleared.
+fn remap_overrides(overrides: &mut UniverseMasteredChannel<Option<u8>>, old: &Device, new: &Device) {
+    let values = old.get_mode().map_channels(new.get_mode()).into_iter()
+        .filter_map(|(old_offset, new_offset)|Some((new_offset, (*overrides.channels.get(old.channel_id(old_offset)?))?)))
+        .collect::<Vec<_>>();
+    for offset in 0..old.get_channels().len() {
+        if let Some(channel) = old.channel_id(offset) {
+            *overrides.channels.get_mut(channel) = None;
+        }
+    }
+    for (offset, value) in values {
+        if let Some(channel) = new.channel_id(offset) {
+            *overrides.channels.get_mut(channel) = Some(value);
+        }
+    }
+}
+
+///Moves the overrides of every device in `moves` from the channels of the old device to the channels of the new device.
+fn move_overrides(overrides: &mut UniverseMasteredChannel<Option<u8>>, moves: &[(&Device, &Device)]) {
+    let mut values = Vec::new();
+    for (old, new) in moves {
+        for offset in 0..old.get_channels().len() {
+            if let Some(channel) = old.channel_id(offset) {
+                values.push((new.channel_id(offset), overrides.channels.get_mut(channel).take()));
+            }
+        }
+    }
+    for (channel, value) in values {
+        if let Some(channel) = channel {
+            *overrides.channels.get_mut(channel) = value;
+        }
+    }
+}
+
+///Moves the overrides of `old` to the channels of `new`, which may be in a different universe.
+fn move_device_overrides(overrides: &mut Universes<UniverseMasteredChannel<Option<u8>>>, (from, old): (ux2::u15, &Device), (to, new): (ux2::u15, &Device)) {
+    if from == to {
+        move_overrides(overrides.create_or_get_universe(to), &[(old, new)]);
+        return;
+    }
+    let from_overrides = overrides.create_or_get_universe(from);
+    let values = (0..old.get_channels().len())
+        .map(|offset|old.channel_id(offset).and_then(|channel|from_overrides.channels.get_mut(channel).take()))
+        .collect::<Vec<_>>();
+    let to_overrides = overrides.create_or_get_universe(to);
+    for (offset, value) in values.into_iter().enumerate() {
+        if let Some(channel) = new.channel_id(offset) {
+            *to_overrides.channels.get_mut(channel) = value;
+        }
+    }
+}
+
 impl Fixtures{
+    fn open_repack_ui(other_app_state: &OtherAppState, universe: usize) {
+        let mut align = false;
+        let mut applied = false;
+        let grid_id = get_id();
+        popup_creator(other_app_state.popups.clone(), format!("Repack Universe {universe}"), move |app, ui|{
+            let Ok(universe_id) = ux2::u15::try_from(universe) else { return };
+            ui.label("Moves all devices towards the start of the universe, to remove unused channels between them.");
+            if ui.checkbox(&mut align, "Align devices to multiples of their channel count").changed() {
+                applied = false;
+            }
+            let Some(devices) = app.serializable_app_data.data.devices.get(universe) else {
+                ui.label("This universe does not exist.");
+                return;
+            };
+            let mut before = devices.iter().cloned().collect::<Vec<_>>();
+            before.sort_by_key(|device|device.start_channel());
+            let after = match devices.repacked(align) {
+                Ok(after) => after,
+                Err(err) => {
+                    ui.label(format!("The universe cannot be repacked: {err}"));
+                    return;
+                }
+            };
+            let mut changed = false;
+            egui::Grid::new(grid_id)
+                .striped(true)
+                .num_columns(4)
+                .show(ui, |ui|{
+                    ui.label("Device");
+                    ui.label("Fixture");
+                    ui.label("Start Channel (Before → After)");
+                    ui.label("End Channel (Before → After)");
+                    ui.end_row();
+                    for (old, new) in before.iter().zip(after.iter()) {
+                        ui.label(old.name.as_ref());
+                        ui.label(old.fixture.get_model().as_ref());
+                        if old.start_channel() == new.start_channel() {
+                            ui.label(old.start_channel().to_string());
+                            ui.label(old.end_channel().to_string());
+                        } else {
+                            changed = true;
+                            ui.strong(format!("{} → {}", old.start_channel(), new.start_channel()));
+                            ui.strong(format!("{} → {}", old.end_channel(), new.end_channel()));
+                        }
+                        ui.end_row();
+                    }
+                });
+            if applied {
+                ui.label("The universe has been repacked.");
+            } else if !changed {
+                ui.label("The universe is already packed.");
+            } else if ui.button("Apply").clicked() {
+                let overrides = app.serializable_app_data.data.overrides.create_or_get_universe(universe_id);
+                move_overrides(overrides, before.iter().zip(after.iter()).collect::<Vec<_>>().as_slice());
+                if let Some(devices) = app.serializable_app_data.data.devices.get_mut(universe) {
+                    *devices = after;
+                }
+                applied = true;
+            }
+        });
+    }
+
+    fn open_edit_device_ui(other_app_state: &OtherAppState, universe: usize, device: &Device) {
+        let device_id = device.get_id();
+        let mut name = device.name.to_string();
+        let mut new_universe = u16::try_from(universe).unwrap_or_default();
+        let mut new_start: u16 = device.start_channel().into();
+        let mut variable_selection = device.get_variable_selection();
+        let mut position_calibration = device.get_position_calibration();
+        let mut response_curve = device.get_response_curve_override().cloned();
+        let mut result: Option<Result<(), String>> = None;
+        let grid_id = get_id();
+        popup_creator(other_app_state.popups.clone(), "Edit Device", move |app, ui|{
+            let devices = &mut app.serializable_app_data.data.devices;
+            let Some((universe, index, device)) = devices.find(device_id)
+                .map(|(universe, index, device)|(universe, index, device.clone()))
+                else {
+                    ui.label("This device no longer exists.");
+                    return;
+                };
+            let channels = device.get_channels().len();
+            egui::Grid::new(grid_id)
+                .show(ui, |ui|{
+                    ui.label("Fixture: ");
+                    ui.label(format!("{} ({})", device.fixture.get_model(), device.get_mode().get_name()));
+                    ui.end_row();
+
+                    ui.label("Name: ");
+                    ui.text_edit_singleline(&mut name);
+                    ui.end_row();
+
+                    ui.label("Universe: ");
+                    egui::DragValue::new(&mut new_universe)
+                        .clamp_range(0u16..=ux2::u15::MAX.into())
+                        .ui(ui);
+                    ui.end_row();
+
+                    ui.label("Start Channel: ");
+                    let max_channel = u16::from(ux2::u9::MAX).saturating_sub(u16::try_from(channels).unwrap_or(u16::MAX));
+                    egui::DragValue::new(&mut new_start)
+                        .clamp_range(0u16..=max_channel)
+                        .ui(ui);
+                    ui.end_row();
+
+                    variable_selection_ui(ui, grid_id, device.get_mode(), &mut variable_selection);
+
+                    if device.get_channels().iter().any(|channel|matches!(channel.get_action(), Action::SimpleAction(SimpleAction::VariableChannelAction(_)))) {
+                        position_calibration_ui(ui, &mut position_calibration);
+                    }
+
+                    if device.get_channels().iter().any(|channel|matches!(channel.get_action(), Action::SimpleAction(action) if action.is_intensity())) {
+                        ui.label("Response Curve: ");
+                        ui.vertical(|ui|{
+                            let mut override_curve = response_curve.is_some();
+                            ui.checkbox(&mut override_curve, "Override Fixture")
+                                .on_hover_text(format!("The fixture uses: {}", device.fixture.get_response_curve()));
+                            if override_curve {
+                                let curve = response_curve.get_or_insert_with(||device.fixture.get_response_curve().clone());
+                                curve_editor(ui, (grid_id, "response_curve"), curve);
+                            } else {
+                                response_curve = None;
+                                curve_preview(ui, device.fixture.get_response_curve());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+            if ui.button("Apply").clicked() {
+                let moved = ux2::u9::try_from(new_start)
+                    .map_err(|err|err.to_string())
+                    .and_then(|new_start|device.with_start_channel(new_start).map_err(|err|err.to_string()))
+                    .and_then(|mut new_device|{
+                        new_device.name = Arc::from(name.as_str());
+                        new_device.set_variable_selection(variable_selection);
+                        new_device.set_position_calibration(position_calibration);
+                        new_device.set_response_curve_override(response_curve.clone());
+                        let from = ux2::u15::try_from(universe).map_err(|err|err.to_string())?;
+                        let to = ux2::u15::try_from(new_universe).map_err(|err|err.to_string())?;
+                        devices.try_move(from, index, to, new_device.clone()).map_err(|err|err.to_string())?;
+                        move_device_overrides(&mut app.serializable_app_data.data.overrides, (from, &device), (to, &new_device));
+                        Ok(())
+                    });
+                result = Some(moved);
+            }
+            match &result {
+                None => {},
+                Some(Ok(())) => {
+                    ui.label("The device has been updated.");
+                },
+                Some(Err(err)) => {
+                    ui.label(format!("The device could not be updated and was left unchanged: {err}"));
+                },
+            }
+        });
+    }
+    fn open_replace_fixture_ui(other_app_state: &OtherAppState, device: &Device) {
+        let device_id = device.get_id();
+        let mut search = String::new();
+        let mut search_index = None;
+        let mut opt_fixture = (Vec::<Arc<str>>::new(), None);
+        let mut mode = 0;
+        let mut new_start = device.start_channel();
+        let mut result: Option<Result<(), String>> = None;
+        let grid_id = get_id();
+        popup_creator(other_app_state.popups.clone(), "Replace Fixture", move |app, ui|{
+            let Some((universe, index, _)) = app.serializable_app_data.data.devices.find(device_id) else {
+                ui.label("This device no longer exists.");
+                return;
+            };
+            let Some(devices) = app.serializable_app_data.data.devices.get_mut(universe) else { return };
+            let Some(device) = devices.iter().nth(index) else { return };
+            ui.label(format!("Device \"{}\": {} ({})", device.name, device.fixture.get_model(), device.get_mode().get_name()));
+
+            ui.horizontal(|ui|{
+                ui.label("Search: ");
+                ui.text_edit_singleline(&mut search);
+            });
+            let prev_path = opt_fixture.0.clone();
+            let index_search = SearchIndex::refresh(&mut search_index, &app.serializable_app_data.fixture_store);
+            fixture_search_list(ui, (grid_id, "search"), index_search, &search, &mut opt_fixture);
+            if prev_path != opt_fixture.0 {
+                mode = 0;
+                result = None;
+            }
+
+            let Some(fixture) = &opt_fixture.1 else { return };
+            ui.horizontal(|ui|{
+                ui.label("Mode: ");
+                egui::ComboBox::from_id_source((grid_id, "mode"))
+                    .selected_text(fixture.get_mode(mode).map_or("", |x|x.get_name().as_ref()))
+                    .show_ui(ui, |ui|{
+                        for (i, fixture_mode) in fixture.get_modes().iter().enumerate() {
+                            ui.selectable_value(&mut mode, i, format!("{} ({} Channels)", fixture_mode.get_name(), fixture_mode.get_channels().len()));
+                        }
+                    });
+            });
+
+            let new_device = match device.repatched(new_start, fixture.clone(), mode) {
+                Ok(new_device) => new_device,
+                Err(err) => {
+                    ui.label(format!("The new fixture cannot be patched at channel {new_start}: {err}"));
+                    return;
+                }
+            };
+
+            let mapping = device.get_mode().map_channels(new_device.get_mode());
+            ui.collapsing("Attribute Mapping", |ui|{
+                egui::Grid::new((grid_id, "mapping"))
+                    .striped(true)
+                    .show(ui, |ui|{
+                        ui.label("Current Channel");
+                        ui.label("New Channel");
+                        ui.end_row();
+                        for offset in 0..device.get_channels().len() {
+                            ui.label(format!("{}: {}", offset+1, device.get_mode().channel_name(offset).unwrap_or_default()));
+                            match mapping.iter().find(|(old, _)|*old == offset) {
+                                Some((_, new)) => ui.label(format!("{}: {}", new+1, new_device.get_mode().channel_name(*new).unwrap_or_default())),
+                                None => ui.label("(dropped)"),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            if !devices.is_free(new_device.start_channel(), new_device.end_channel(), Some(index)) {
+                ui.label(format!("The new fixture would overlap other devices when starting at channel {new_start}."));
+                match devices.find_free_range(new_device.get_channels().len(), ux2::u9::MIN, Some(index)) {
+                    Some(free) => if ui.button(format!("Re-address to channel {free}")).clicked() {
+                        new_start = free;
+                    },
+                    None => {
+                        ui.label("There is no free range in this universe, that is large enough.");
+                    }
+                }
+                return;
+            }
+
+            if ui.button("Replace").clicked() {
+                match devices.try_replace(index, new_device.clone()) {
+                    Ok(old_device) => {
+                        if let Ok(universe) = ux2::u15::try_from(universe) {
+                            let overrides = app.serializable_app_data.data.overrides.create_or_get_universe(universe);
+                            remap_overrides(overrides, &old_device, &new_device);
+                        }
+                        result = Some(Ok(()));
+                    },
+                    Err(err) => result = Some(Err(err.to_string())),
+                }
+            }
+            match &result {
+                None => {},
+                Some(Ok(())) => {
+                    ui.label("The fixture has been replaced.");
+                },
+                Some(Err(err)) => {
+                    ui.label(format!("The fixture could not be replaced: {err}"));
+                },
+            }
+        });
+    }
+
+    ///Patches `quantity` devices, starting at `start_id` in `universe`.
+    ///See [`Universes::find_free_ranges`] for how the devices get addressed.
+    ///`{n}` in `name` gets replaced with the 1-based number of the device.
+    ///Either all or no devices get added.
+    #[allow(clippy::too_many_arguments)] //these are just the fields of the Add Fixture popup
+    fn bulk_add(devices: &mut Universes<UniverseDevices>, name: &str, fixture: &Fixture, mode: usize, variable_selection: VariableSelection, universe: u16, start_id: u16, quantity: usize, gap: usize) -> Result<usize, String> {
+        let channels = fixture.get_mode(mode).ok_or(DeviceError::InvalidMode(mode)).map_err(|err|err.to_string())?.get_channels().len();
+        let universe = ux2::u15::try_from(universe).map_err(|err|err.to_string())?;
+        let start_id = ux2::u9::try_from(start_id).map_err(|err|err.to_string())?;
+        let placements = devices.find_free_ranges(quantity, channels, universe, start_id, gap).map_err(|err|err.to_string())?;
+        let new_devices = placements.into_iter()
+            .enumerate()
+            .map(|(i, (universe, start_channel))|{
+                let name = name.replace("{n}", (i+1).to_string().as_str());
+                Device::new(Arc::from(name), start_channel, fixture.clone(), mode).map(|mut device|{
+                    device.set_variable_selection(variable_selection);
+                    (universe, device)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err|err.to_string())?;
+        //insert into a copy, so that a failing device doesn't leave the others patched
+        let mut patched = devices.clone();
+        for (universe, device) in new_devices {
+            patched.create_or_get_universe(universe).try_insert(device).map_err(|err|err.to_string())?;
+        }
+        *devices = patched;
+        Ok(quantity)
+    }
+
     fn open_add_fixture_ui(other_app_state: &OtherAppState) {
-        let mut name = "";
+        let mut name = String::new();
         let mut universe = 1;
         let mut start_id = 0;
-        let mut device_err = None;
-        let mut device_insert_err = None;
+        let mut quantity = 1usize;
+        let mut gap = 0usize;
+        let mut add_result: Option<Result<usize, String>> = None;
         let mut opt_fixture = (Vec::<Arc<str>>::new(), None);
+        let mut mode = 0;
+        let mut variable_selection = VariableSelection::default();
+        let mut search = String::new();
+        let mut search_index = None;
         let grid_id = get_id();
         popup_creator(other_app_state.popups.clone(), "Add Fixture", move |app, ui|{
             egui::Grid::new(grid_id)
                 .show(ui, |ui|{
                     ui.label("Name: ");
-                    ui.text_edit_singleline(&mut name);
+                    ui.text_edit_singleline(&mut name)
+                        .on_hover_text("\"{n}\" gets replaced with the number of the device (e.g. \"Bar {n}\")");
+                    ui.end_row();
+
+                    ui.label("Quantity: ");
+                    egui::DragValue::new(&mut quantity)
+                        .clamp_range(1usize..=usize::from(ux2::u9::MAX))
+                        .ui(ui);
                     ui.end_row();
 
                     ui.label("Universe: ");
@@ -36,19 +512,26 @@ impl Fixtures{
                     let max_channel =
                         ux2::u9::checked_sub(
                             ux2::u9::MAX,
-                            opt_fixture.1.as_ref().map_or(
+                            opt_fixture.1.as_ref().and_then(|x:&Fixture|x.get_mode(mode)).map_or(
                                 ux2::u9::MIN,
-                                |x:&Fixture| ux2::u9::try_from(
+                                |x:&FixtureMode| ux2::u9::try_from(
                                     x.get_channels().len()
                                 ).unwrap_or(ux2::u9::MAX)
                             )).unwrap_or(ux2::u9::MIN);
-                    let ui_channel = {
-                        egui::DragValue::new(&mut start_id)
-                            .clamp_range(0u16..=max_channel.into())
-                            .ui(ui)
-                    };
+                    egui::DragValue::new(&mut start_id)
+                        .clamp_range(0u16..=max_channel.into())
+                        .ui(ui)
+                        .on_hover_text("The first free channel at or after this channel is used.");
                     ui.end_row();
 
+                    ui.label("Address Gap: ");
+                    egui::DragValue::new(&mut gap)
+                        .clamp_range(0usize..=usize::from(ux2::u9::MAX))
+                        .ui(ui)
+                        .on_hover_text("Unused channels between two consecutive devices");
+                    ui.end_row();
+
+                    let prev_path = opt_fixture.0.clone();
                     ui.menu_button(format!("{} Fixture", if opt_fixture.0.is_empty() {"Set"} else {"Change"}), |ui|{
                         app.serializable_app_data.fixture_store.build_menu(ui, &mut opt_fixture);
                     });
@@ -64,38 +547,85 @@ impl Fixtures{
                         ui.label(path);
                     }
                     ui.end_row();
+
+                    ui.label("Search: ");
+                    ui.text_edit_singleline(&mut search)
+                        .on_hover_text("Searches manufacturer, model, type and channel count (e.g. \"13ch\")");
+                    ui.end_row();
+
+                    let index = SearchIndex::refresh(&mut search_index, &app.serializable_app_data.fixture_store);
+                    match fixture_search_list(ui, (grid_id, "search"), index, &search, &mut opt_fixture) {
+                        Some(fixture) => {
+                            if let Some(fixture_mode) = fixture.get_mode(0) {
+                                mode_preview(ui, (grid_id, "preview"), fixture_mode);
+                            }
+                        },
+                        None => {
+                            if let Some(fixture_mode) = opt_fixture.1.as_ref().and_then(|fixture|fixture.get_mode(mode)) {
+                                mode_preview(ui, (grid_id, "preview"), fixture_mode);
+                            }
+                        }
+                    }
+                    ui.end_row();
+                    if prev_path != opt_fixture.0 {
+                        mode = 0;
+                        variable_selection = VariableSelection::default();
+                    }
+
+                    if let Some(fixture) = &opt_fixture.1 {
+                        ui.label("Mode: ");
+                        egui::ComboBox::from_id_source((grid_id, "mode"))
+                            .selected_text(fixture.get_mode(mode).map_or("", |x|x.get_name().as_ref()))
+                            .show_ui(ui, |ui|{
+                                for (i, fixture_mode) in fixture.get_modes().iter().enumerate() {
+                                    ui.selectable_value(&mut mode, i, format!("{} ({} Channels)", fixture_mode.get_name(), fixture_mode.get_channels().len()));
+                                }
+                            });
+                        ui.end_row();
+
+                        if let Some(fixture_mode) = fixture.get_mode(mode) {
+                            variable_selection_ui(ui, grid_id, fixture_mode, &mut variable_selection);
+                        }
+                    }
                     ui.horizontal(|ui|{
                         if let Some(fixture) = &opt_fixture.1 {
                             if ui.button("Add").clicked() {
-                                match Device::new_u16(Arc::from(name), start_id, fixture.clone()) {
-                                    Ok(device) => {
-                                        device_err = None;
-                                        let universe = app.serializable_app_data.data.devices.create_or_get_universe(ux2::u15::new(universe));
-                                        match universe.try_insert(device){
-                                            Ok(()) => {
-                                                device_insert_err = None;
-                                            },
-                                            Err(err) => {
-                                                device_insert_err = Some(err);
-                                            }
-                                        }
-                                    },
-                                    Err(err) => device_err = Some(err),
-                                };
+                                add_result = Some(Self::bulk_add(
+                                    &mut app.serializable_app_data.data.devices,
+                                    name.as_str(),
+                                    fixture,
+                                    mode,
+                                    variable_selection,
+                                    universe,
+                                    start_id,
+                                    quantity,
+                                    gap,
+                                ));
                             }
                         }
 
-                        if let Some(err) = &device_err{
-                            ui_channel.ctx.debug_painter().error(ui_channel.rect.left_bottom(), err);
-                        }
-                        if let Some(err) = device_insert_err{
-                            ui_channel.ctx.debug_painter().error(ui_channel.rect.left_bottom(), err);
+                        match &add_result {
+                            None => {},
+                            Some(Ok(1)) => {
+                                ui.label("Added the device.");
+                            },
+                            Some(Ok(count)) => {
+                                ui.label(format!("Added {count} devices."));
+                            },
+                            Some(Err(err)) => {
+                                ui.label(format!("No devices were added: {err}"));
+                            },
                         }
 
                         if ui.button("Clear").clicked() {
                             universe = 1;
                             start_id = 0;
+                            quantity = 1;
+                            gap = 0;
+                            add_result = None;
                             opt_fixture = (Vec::new(), None);
+                            mode = 0;
+                            variable_selection = VariableSelection::default();
                         }
                     });
                 });
@@ -106,29 +636,47 @@ impl Fixtures{
 impl SubMenu for Fixtures{
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame, serializable_app_data: &mut SerializableAppData, other_app_state: &mut OtherAppState, _: mode::AppMode) {
         let mut remove_list = Vec::new();
+        let mut move_list = Vec::new();
         CentralPanel::default().show(ctx, |ui| {
             for (universe, devices) in serializable_app_data.data.devices.iter().enumerate() {
                 if devices.is_empty() {continue;}
                 let universe_str =format!("Universe {universe}");
                 ui.collapsing(&universe_str, |ui|{
+                    if ui.button("Repack").clicked() {
+                        Self::open_repack_ui(other_app_state, universe);
+                    }
+                    ui.collapsing("Patch Overview", |ui|{
+                        if let Some((device_id, start_channel)) = patch_overview::patch_overview(ui, universe, devices) {
+                            move_list.push((device_id, start_channel));
+                        }
+                    });
                     egui::Grid::new("fixtures:".to_string().add(universe_str.as_str()))
-                        .num_columns(5)
+                        .num_columns(6)
                         .show(ui, |ui|{
                             ui.label("Device Id");
                             ui.label("Fixture Name");
+                            ui.label("Mode");
                             ui.label("Start Channel");
                             ui.label("End Channel");
                             ui.label("Action");
                             ui.end_row();
-                            for (dev_id, device) in devices.iter().enumerate(){
-                                ui.label(dev_id.to_string());
+                            for device in devices {
+                                ui.label(device.get_id().to_string());
                                 ui.label(device.fixture.get_model().as_ref());
+                                ui.label(device.get_mode().get_name().as_ref());
                                 ui.label(device.start_channel().to_string());
                                 ui.label(device.end_channel().to_string());
-                                if ui.button("Remove").clicked() {
-                                    remove_list.push((universe, dev_id));
-                                }
-                                //todo: add edit button
+                                ui.horizontal(|ui|{
+                                    if ui.button("Remove").clicked() {
+                                        remove_list.push(device.get_id());
+                                    }
+                                    if ui.button("Edit").clicked() {
+                                        Self::open_edit_device_ui(other_app_state, universe, device);
+                                    }
+                                    if ui.button("Replace Fixture").clicked() {
+                                        Self::open_replace_fixture_ui(other_app_state, device);
+                                    }
+                                });
                                 ui.end_row();
                             }
                         });
@@ -137,14 +685,31 @@ impl SubMenu for Fixtures{
             if serializable_app_data.data.devices.is_empty(){
                 ui.label("No Fixtures have been added in any Univserse. Please get started, by adding a Fixture.");
             }
-            for (universe, device_id) in remove_list{
-                if let Some(devices) = serializable_app_data.data.devices.get_mut(universe){
-                    devices.remove(device_id);
+            for (device_id, start_channel) in move_list {
+                let devices = &mut serializable_app_data.data.devices;
+                let Some((universe, index, old)) = devices.find(device_id).map(|(universe, index, device)|(universe, index, device.clone())) else { continue };
+                let Ok(new) = old.with_start_channel(start_channel) else { continue };
+                let Ok(universe_id) = ux2::u15::try_from(universe) else { continue };
+                match devices.get_mut(universe).map(|devices|devices.try_replace(index, new.clone())) {
+                    Some(Ok(_)) => move_overrides(serializable_app_data.data.overrides.create_or_get_universe(universe_id), &[(&old, &new)]),
+                    Some(Err(err)) => log::warn!("Could not move device {device_id}: {err}"),
+                    None => {},
+                }
+            }
+            for device_id in remove_list{
+                let devices = &mut serializable_app_data.data.devices;
+                if let Some((universe, index, _)) = devices.find(device_id) {
+                    if let Some(devices) = devices.get_mut(universe) {
+                        devices.remove(index);
+                    }
                 }
             }
             if ui.button("Add Fixture").clicked() {
                 Self::open_add_fixture_ui(other_app_state)
             }
+            ui.separator();
+            ui.heading("Groups");
+            groups::groups_ui(ui, &mut serializable_app_data.data);
         });
     }
 }
\ No newline at end of file
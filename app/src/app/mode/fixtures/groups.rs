@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use crate::app::common_data::CommonData;
+use crate::artnet::group::DeviceGroup;
+
+///Lists all device groups and allows editing their name and members.
+pub(super) fn groups_ui(ui: &mut egui::Ui, data: &mut CommonData) {
+    let mut remove = None;
+    for (group_index, group) in data.groups.iter_mut().enumerate() {
+        let id = ui.id().with(("group", group_index));
+        egui::CollapsingHeader::new(format!("{} ({} Devices)", group.name, group.len()))
+            .id_source(id)
+            .show(ui, |ui|{
+                ui.horizontal(|ui|{
+                    ui.label("Name: ");
+                    let mut name = group.name.to_string();
+                    if ui.text_edit_singleline(&mut name).changed() {
+                        group.name = Arc::from(name);
+                    }
+                    if ui.button("Remove Group").clicked() {
+                        remove = Some(group_index);
+                    }
+                });
+                egui::Grid::new(id.with("members"))
+                    .striped(true)
+                    .num_columns(4)
+                    .show(ui, |ui|{
+                        ui.label("Member");
+                        ui.label("Universe");
+                        ui.label("Start Channel");
+                        ui.label("Device");
+                        ui.end_row();
+                        for (universe, devices) in data.devices.iter().enumerate() {
+                            for device in devices {
+                                let device_id = device.get_id();
+                                let mut member = group.contains(device_id);
+                                if ui.checkbox(&mut member, "").changed() {
+                                    if member {
+                                        group.add(device_id);
+                                    } else {
+                                        group.remove(device_id);
+                                    }
+                                }
+                                ui.label(universe.to_string());
+                                ui.label(device.start_channel().to_string());
+                                ui.label(format!("{} ({})", device.name, device.fixture.get_model()));
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+    }
+    if let Some(group_index) = remove {
+        data.groups.remove(group_index);
+    }
+    if ui.button("Add Group").clicked() {
+        data.groups.push(DeviceGroup::new(Arc::from(format!("Group {}", data.groups.len() + 1))));
+    }
+}
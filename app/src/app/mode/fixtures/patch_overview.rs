@@ -0,0 +1,147 @@
+use egui::{Color32, Sense, Stroke, Vec2};
+use crate::artnet::fixture::DeviceId;
+use crate::artnet::universe::UniverseDevices;
+
+///Channels per row of the overview. 16 rows of 32 channels show a whole universe.
+const CHANNELS_PER_ROW: u16 = 32;
+const CELL_SIZE: f32 = 14.;
+const ROW_LABEL_WIDTH: f32 = 28.;
+const FREE_COLOR: Color32 = Color32::from_rgb(30, 70, 30);
+const VALID_DROP_COLOR: Color32 = Color32::from_rgb(80, 220, 80);
+const INVALID_DROP_COLOR: Color32 = Color32::from_rgb(220, 60, 60);
+///Devices get colored by their index in the universe. Neighbouring devices always get a different color.
+const DEVICE_COLORS: [Color32; 8] = [
+    Color32::from_rgb(70, 110, 200),
+    Color32::from_rgb(200, 130, 50),
+    Color32::from_rgb(150, 80, 190),
+    Color32::from_rgb(50, 170, 170),
+    Color32::from_rgb(190, 70, 110),
+    Color32::from_rgb(160, 160, 60),
+    Color32::from_rgb(90, 140, 90),
+    Color32::from_rgb(120, 100, 80),
+];
+
+///The device, that is currently dragged and the offset of the channel it was grabbed at.
+#[derive(Debug, Copy, Clone)]
+struct DragState{
+    device: DeviceId,
+    grab_offset: usize,
+}
+
+///Shows all channels of a universe as a grid, colored by the device occupying them.
+///
+///Devices can be dragged to a new start channel.
+///Returns the id of the dragged device and its new start channel, once a device got dropped onto free channels.
+pub(super) fn patch_overview(ui: &mut egui::Ui, universe: usize, devices: &UniverseDevices) -> Option<(DeviceId, ux2::u9)> {
+    let drag_id = ui.id().with(("patch_overview", universe));
+    let mut drag_state = ui.data_mut(|data|data.get_temp::<DragState>(drag_id));
+    let dragged_device = drag_state.and_then(|state|{
+        let index = devices.position(state.device)?;
+        devices.iter().nth(index).map(|device|(state, index, device))
+    });
+    let mut drop_target: Option<(DeviceId, ux2::u9)> = None;
+    let mut drop_range: Option<(u16, u16, bool)> = None;
+
+    let cell_ids = (0..=u16::from(ux2::u9::MAX)).collect::<Vec<_>>();
+    ui.vertical(|ui|{
+        ui.spacing_mut().item_spacing = Vec2::splat(1.);
+        let mut cells = Vec::with_capacity(cell_ids.len());
+        for row in cell_ids.chunks(usize::from(CHANNELS_PER_ROW)) {
+            ui.horizontal(|ui|{
+                if let Some(first) = row.first() {
+                    ui.add_sized([ROW_LABEL_WIDTH, CELL_SIZE], egui::Label::new((first+1).to_string()));
+                }
+                for &channel in row {
+                    let (rect, response) = ui.allocate_exact_size(Vec2::splat(CELL_SIZE), Sense::click_and_drag());
+                    cells.push((channel, rect, response));
+                }
+            });
+        }
+
+        //find out, where the dragged device would be dropped, before painting the cells.
+        if let Some((state, index, device)) = dragged_device {
+            let hovered = cells.iter().find(|(_, rect, _)|ui.rect_contains_pointer(*rect));
+            if let Some((channel, _, _)) = hovered {
+                let channels = device.get_channels().len();
+                let start = usize::from(*channel).saturating_sub(state.grab_offset);
+                let valid = ux2::u9::try_from(start).ok()
+                    .zip(ux2::u9::try_from(start + channels).ok())
+                    .filter(|(start, end)|devices.is_free(*start, *end, Some(index)));
+                let start_u16 = u16::try_from(start).unwrap_or(u16::MAX);
+                let end_u16 = u16::try_from(start + channels).unwrap_or(u16::MAX);
+                drop_range = Some((start_u16, end_u16, valid.is_some()));
+                drop_target = valid.map(|(start, _)|(state.device, start));
+            }
+        }
+
+        for (channel, rect, response) in cells {
+            let Ok(channel_id) = ux2::u9::try_from(channel) else { continue };
+            let occupant = devices.iter()
+                .enumerate()
+                .find(|(_, device)|device.start_channel() <= channel_id && channel_id < device.end_channel());
+            let fill = occupant.map_or(FREE_COLOR, |(index, _)|DEVICE_COLORS.get(index % DEVICE_COLORS.len()).copied().unwrap_or(FREE_COLOR));
+            let painter = ui.painter();
+            painter.rect_filled(rect, 1., fill);
+            if let Some((start, end, valid)) = drop_range {
+                if (start..end).contains(&channel) {
+                    painter.rect_stroke(rect, 1., Stroke::new(2., if valid {VALID_DROP_COLOR} else {INVALID_DROP_COLOR}));
+                }
+            }
+
+            match occupant {
+                Some((_, device)) => {
+                    let offset = usize::from(channel) - usize::from(device.start_channel());
+                    if response.drag_started() {
+                        drag_state = Some(DragState{
+                            device: device.get_id(),
+                            grab_offset: offset,
+                        });
+                    }
+                    response.on_hover_text(format!(
+                        "Channel {}\n{} ({})\n{}",
+                        channel + 1,
+                        device.name,
+                        device.fixture.get_model(),
+                        device.get_mode().channel_name(offset).unwrap_or_default(),
+                    ));
+                },
+                None => {
+                    let free = free_range_around(devices, channel);
+                    response.on_hover_text(format!("Channel {}\nfree ({}-{})", channel + 1, free.0 + 1, free.1));
+                },
+            }
+        }
+        if drag_state.is_some() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        }
+    });
+
+    let released = ui.input(|input|input.pointer.any_released());
+    ui.data_mut(|data|{
+        if released {
+            data.remove::<DragState>(drag_id);
+        } else if let Some(state) = drag_state {
+            data.insert_temp(drag_id, state);
+        }
+    });
+    if released {
+        drop_target
+    } else {
+        None
+    }
+}
+
+///Returns the free channels around `channel` as start (inclusive) and end (exclusive).
+fn free_range_around(devices: &UniverseDevices, channel: u16) -> (u16, u16) {
+    let start = devices.iter()
+        .map(|device|u16::from(device.end_channel()))
+        .filter(|end|*end <= channel)
+        .max()
+        .unwrap_or(0);
+    let end = devices.iter()
+        .map(|device|u16::from(device.start_channel()))
+        .filter(|start|*start > channel)
+        .min()
+        .unwrap_or(u16::from(ux2::u9::MAX) + 1);
+    (start, end)
+}
@@ -0,0 +1,185 @@
+use egui::{CentralPanel, SidePanel, Widget};
+use serde_derive::{Deserialize, Serialize};
+use crate::app::{mode, OtherAppState, SerializableAppData, SubMenu};
+use crate::app::mode::xy_pad::xy_pad;
+use crate::app::programmer::{apply, percent_to_dmx, position, release, ApplyReport, AttributeValue};
+use crate::artnet::fixture::DeviceId;
+use crate::degree::{deg_to_microarcseconds, microarcseconds_to_deg};
+
+///Sets attributes of the selected devices by their meaning (e.g. color or pan), instead of by channel.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(super) struct Programmer{
+    selection: Vec<DeviceId>,
+    dimmer_percent: u8,
+    color: [u8; 3],
+    pan_degrees: u32,
+    tilt_degrees: u32,
+    #[serde(skip)]
+    last_report: Option<(&'static str, ApplyReport)>,
+}
+
+impl Default for Programmer {
+    fn default() -> Self {
+        Self{
+            selection: Vec::new(),
+            dimmer_percent: 100,
+            color: [u8::MAX; 3],
+            pan_degrees: 0,
+            tilt_degrees: 0,
+            last_report: None,
+        }
+    }
+}
+
+impl Programmer {
+    fn selection_ui(&mut self, serializable_app_data: &SerializableAppData, ui: &mut egui::Ui) {
+        let data = &serializable_app_data.data;
+        ui.heading("Selection");
+        ui.horizontal(|ui|{
+            if ui.button("Select All").clicked() {
+                self.selection = data.devices.iter()
+                    .flat_map(|devices|devices.iter().map(|device|device.get_id()))
+                    .collect();
+            }
+            if ui.button("Clear").clicked() {
+                self.selection.clear();
+            }
+        });
+        if !data.groups.is_empty() {
+            ui.label("Groups:");
+            ui.horizontal_wrapped(|ui|{
+                for group in &data.groups {
+                    if ui.button(group.name.as_ref()).on_hover_text("Adds all devices of this group to the selection").clicked() {
+                        for id in group.members() {
+                            if !self.selection.contains(id) {
+                                self.selection.push(*id);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .show(ui, |ui|{
+                for (universe, devices) in data.devices.iter().enumerate() {
+                    for device in devices {
+                        let mut selected = self.selection.contains(&device.get_id());
+                        if ui.checkbox(&mut selected, format!("{} (Universe {universe}, Channel {})", device.name, usize::from(device.start_channel()) + 1)).changed() {
+                            if selected {
+                                self.selection.push(device.get_id());
+                            } else {
+                                self.selection.retain(|id|*id != device.get_id());
+                            }
+                        }
+                    }
+                }
+            });
+    }
+
+    fn attributes_ui(&mut self, serializable_app_data: &mut SerializableAppData, ui: &mut egui::Ui) {
+        ui.heading("Attributes");
+        ui.label(format!("{} Devices selected", self.selection.len()));
+        //the largest pan and tilt range of all selected devices
+        let (pan_max, tilt_max) = self.selection.iter()
+            .filter_map(|id|{
+                let (universe, _, device) = serializable_app_data.data.devices.find(*id)?;
+                let overrides = &serializable_app_data.data.overrides.get(universe)?.channels;
+                Some(position(overrides, device))
+            })
+            .fold((0, 0), |(pan_max, tilt_max), (pan, tilt)|(
+                pan.map_or(pan_max, |pan|pan_max.max(microarcseconds_to_deg(pan.range))),
+                tilt.map_or(tilt_max, |tilt|tilt_max.max(microarcseconds_to_deg(tilt.range))),
+            ));
+        let pan_max = u32::try_from(pan_max).unwrap_or(u32::MAX);
+        let tilt_max = u32::try_from(tilt_max).unwrap_or(u32::MAX);
+        let mut changed = Vec::new();
+        egui::Grid::new("programmer:attributes")
+            .num_columns(2)
+            .show(ui, |ui|{
+                ui.label("Dimmer: ");
+                if egui::Slider::new(&mut self.dimmer_percent, 0..=100).suffix("%").ui(ui).changed() {
+                    changed.push(("Dimmer", AttributeValue::Dimmer(percent_to_dmx(self.dimmer_percent))));
+                }
+                ui.end_row();
+
+                ui.label("Color: ");
+                if egui::color_picker::color_edit_button_srgb(ui, &mut self.color).changed() {
+                    changed.push(("Color", AttributeValue::Color(self.color)));
+                }
+                ui.end_row();
+
+                ui.label("Pan: ");
+                if egui::DragValue::new(&mut self.pan_degrees).clamp_range(0..=pan_max).suffix("°").ui(ui).changed() {
+                    changed.push(("Pan", AttributeValue::Pan(deg_to_microarcseconds(self.pan_degrees))));
+                }
+                ui.end_row();
+
+                ui.label("Tilt: ");
+                if egui::DragValue::new(&mut self.tilt_degrees).clamp_range(0..=tilt_max).suffix("°").ui(ui).changed() {
+                    changed.push(("Tilt", AttributeValue::Tilt(deg_to_microarcseconds(self.tilt_degrees))));
+                }
+                ui.end_row();
+
+                ui.label("Position: ");
+                if xy_pad(ui, &mut self.pan_degrees, pan_max, &mut self.tilt_degrees, tilt_max).changed() {
+                    changed.push(("Pan", AttributeValue::Pan(deg_to_microarcseconds(self.pan_degrees))));
+                    changed.push(("Tilt", AttributeValue::Tilt(deg_to_microarcseconds(self.tilt_degrees))));
+                }
+                ui.end_row();
+            });
+        for (attribute, value) in changed {
+            self.last_report = Some((attribute, apply(&mut serializable_app_data.data, self.selection.as_slice(), value)));
+        }
+        if !serializable_app_data.data.groups.is_empty() {
+            ui.separator();
+            ui.heading("Groups");
+            egui::Grid::new("programmer:group_colors")
+                .num_columns(3)
+                .show(ui, |ui|{
+                    ui.label("Group");
+                    ui.label("Color");
+                    ui.label("Master")
+                        .on_hover_text("Scales the dimmer and color channels of all devices in the group");
+                    ui.end_row();
+                    for index in 0..serializable_app_data.data.groups.len() {
+                        let Some(group) = serializable_app_data.data.groups.get(index) else { continue };
+                        ui.label(group.name.as_ref());
+                        let color_id = ui.id().with(("group_color", index));
+                        let mut color = ui.data_mut(|data|*data.get_temp_mut_or(color_id, [u8::MAX; 3]));
+                        if egui::color_picker::color_edit_button_srgb(ui, &mut color).changed() {
+                            ui.data_mut(|data|data.insert_temp(color_id, color));
+                            let members = group.members().to_vec();
+                            self.last_report = Some(("Color", apply(&mut serializable_app_data.data, members.as_slice(), AttributeValue::Color(color))));
+                        }
+                        if let Some(group) = serializable_app_data.data.groups.get_mut(index) {
+                            egui::Slider::new(&mut group.master, u8::MIN..=u8::MAX).ui(ui);
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+        if ui.button("Release").on_hover_text("Removes all overrides of the selected devices").clicked() {
+            release(&mut serializable_app_data.data, self.selection.as_slice());
+            self.last_report = None;
+        }
+        if let Some((attribute, report)) = &self.last_report {
+            ui.label(format!("{attribute}: set on {} devices.", report.applied));
+            if report.skipped > 0 {
+                ui.label(format!("{} devices don't have this attribute and were skipped.", report.skipped));
+            }
+            if report.missing > 0 {
+                ui.label(format!("{} selected devices don't exist anymore.", report.missing));
+            }
+        }
+    }
+}
+
+impl SubMenu for Programmer {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame, serializable_app_data: &mut SerializableAppData, _: &mut OtherAppState, _: mode::AppMode) {
+        SidePanel::left("programmer:selection")
+            .show(ctx, |ui|self.selection_ui(serializable_app_data, ui));
+        CentralPanel::default()
+            .show(ctx, |ui|self.attributes_ui(serializable_app_data, ui));
+    }
+}
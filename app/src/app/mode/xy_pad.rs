@@ -0,0 +1,32 @@
+use egui::{Sense, Stroke, Vec2};
+
+const PAD_SIZE: f32 = 150.;
+const HANDLE_RADIUS: f32 = 5.;
+
+///A square pad, that sets pan (horizontal) and tilt (vertical) at once by clicking or dragging.
+///`pan` and `tilt` are in whole degrees and get clamped to `pan_max` and `tilt_max`.
+///An axis with a maximum of 0 is not changed by the pad.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)] //the values are small degrees and get clamped by remap_clamp
+pub(super) fn xy_pad(ui: &mut egui::Ui, pan: &mut u32, pan_max: u32, tilt: &mut u32, tilt_max: u32) -> egui::Response {
+    let (rect, mut response) = ui.allocate_exact_size(Vec2::splat(PAD_SIZE), Sense::click_and_drag());
+    if let Some(pointer) = response.interact_pointer_pos() {
+        if pan_max > 0 {
+            *pan = egui::remap_clamp(pointer.x, rect.x_range(), 0.0..=pan_max as f32).round() as u32;
+        }
+        if tilt_max > 0 {
+            *tilt = egui::remap_clamp(pointer.y, rect.y_range(), 0.0..=tilt_max as f32).round() as u32;
+        }
+        response.mark_changed();
+    }
+
+    let visuals = ui.style().interact(&response);
+    let painter = ui.painter();
+    painter.rect(rect, visuals.rounding, ui.visuals().extreme_bg_color, visuals.bg_stroke);
+    let x = egui::remap_clamp((*pan).min(pan_max) as f32, 0.0..=pan_max.max(1) as f32, rect.x_range());
+    let y = egui::remap_clamp((*tilt).min(tilt_max) as f32, 0.0..=tilt_max.max(1) as f32, rect.y_range());
+    let stroke = Stroke::new(1., visuals.fg_stroke.color);
+    painter.vline(x, rect.y_range(), stroke);
+    painter.hline(rect.x_range(), y, stroke);
+    painter.circle_filled(egui::pos2(x, y), HANDLE_RADIUS, visuals.fg_stroke.color);
+    response
+}
@@ -0,0 +1,214 @@
+use crate::app::common_data::CommonData;
+use crate::artnet::fixture::channel::{join_u16, Action, Channel, SimpleAction};
+use crate::artnet::fixture::calibration::PositionAxis;
+use crate::artnet::fixture::variables::VariableChannelAction;
+use crate::artnet::fixture::{Device, DeviceId};
+use crate::artnet::universe::UniverseChannels;
+use crate::color::color_channel_values;
+
+///A value for an attribute of a device, independent of which channels of the device control that attribute.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(super) enum AttributeValue{
+    Dimmer(u8),
+    ///An RGB color, that gets converted to whatever color channels a device has.
+    Color([u8; 3]),
+    ///Pan in micro-arc-seconds, relative to the start of the pan range.
+    Pan(u64),
+    ///Tilt in micro-arc-seconds, relative to the start of the tilt range.
+    Tilt(u64),
+}
+
+///The result of applying an [`AttributeValue`] to several devices.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(super) struct ApplyReport{
+    ///Devices, that had the attribute and have been changed
+    pub applied: usize,
+    ///Devices, that don't have the attribute
+    pub skipped: usize,
+    ///Devices, that don't exist anymore
+    pub missing: usize,
+}
+
+///The pan or tilt of a device.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(super) struct Position{
+    ///The current position in micro-arc-seconds. `None`, if the channels are not overridden.
+    pub value: Option<u64>,
+    ///The total range in micro-arc-seconds.
+    pub range: u64,
+}
+
+///Converts a percentage (0-100) into a dmx value.
+pub(super) fn percent_to_dmx(percent: u8) -> u8 {
+    u8::try_from(u16::from(percent.min(100)) * u16::from(u8::MAX) / 100).unwrap_or(u8::MAX)
+}
+
+///Computes the values of all channels of `device`, that control the attribute of `value`.
+///Returns (offset, dmx value) pairs. The result is empty, if `device` doesn't have that attribute.
+fn channel_values(device: &Device, value: AttributeValue) -> Vec<(usize, u8)> {
+    let channels = device.get_channels();
+    if let AttributeValue::Color(rgb) = value {
+        let (offsets, roles): (Vec<_>, Vec<_>) = channels.iter()
+            .enumerate()
+            .filter_map(|(offset, channel)|match channel.get_action() {
+                Action::SimpleAction(SimpleAction::IntensityColor(role)) => Some((offset, *role)),
+                _ => None,
+            })
+            .unzip();
+        return offsets.into_iter()
+            .zip(color_channel_values(rgb, roles.as_slice()))
+            .filter_map(|(offset, value)|value.map(|value|(offset, value)))
+            .collect();
+    }
+    //the physical axis, that needs to be changed, after applying the calibration of the device
+    let calibration = device.get_position_calibration();
+    let position = match value {
+        AttributeValue::Pan(angle) => Some((calibration.physical_axis(PositionAxis::Pan), angle)),
+        AttributeValue::Tilt(angle) => Some((calibration.physical_axis(PositionAxis::Tilt), angle)),
+        AttributeValue::Dimmer(_) | AttributeValue::Color(_) => None,
+    };
+    let mut values = Vec::new();
+    for (offset, channel) in channels.iter().enumerate() {
+        let Action::SimpleAction(action) = channel.get_action() else { continue };
+        match (action, value) {
+            //257 maps 255 to u16::MAX, so that the fine channel of a 16-bit dimmer gets set as well
+            (SimpleAction::IntensityMasterDimmer, AttributeValue::Dimmer(dimmer)) => values.extend(channel.u16_values(offset, u16::from(dimmer) * 257)),
+            (SimpleAction::VariableChannelAction(action), _) => {
+                if let Some((axis, angle)) = position.filter(|(axis, _)|position_axis(action) == Some(*axis)) {
+                    position_values(device, channel, offset, action, axis, angle, &mut values);
+                }
+            },
+            _ => {},
+        }
+    }
+    values
+}
+
+///Returns the axis, that `action` controls. Legacy independent fine channels don't control an axis on their own.
+const fn position_axis(action: &VariableChannelAction) -> Option<PositionAxis> {
+    match action {
+        VariableChannelAction::PositionPan(_) => Some(PositionAxis::Pan),
+        VariableChannelAction::PositionTilt(_) => Some(PositionAxis::Tilt),
+        VariableChannelAction::PositionPanFine(_) |
+        VariableChannelAction::PositionTiltFine(_) => None,
+    }
+}
+
+///Computes the values of a position channel and its fine channel, if there is one.
+///`angle` is the logical position, which gets calibrated for the physical `axis` first.
+fn position_values(device: &Device, channel: &Channel, offset: usize, action: &VariableChannelAction, axis: PositionAxis, angle: u64, values: &mut Vec<(usize, u8)>) {
+    let resolved = action.resolve(device.get_variable_selection());
+    let angle = device.get_position_calibration().calibrate(axis, angle, resolved.range());
+    //the coarse byte equals the 8-bit value, so channels without a fine channel get the same value as before
+    values.extend(channel.u16_values(offset, resolved.scale_to_range_u16(angle)));
+}
+
+///Sets the attribute of `value` on `device` by writing into `overrides`, which need to be the overrides of the universe of `device`.
+///Returns false, if `device` doesn't have that attribute.
+pub(super) fn apply_to_device(overrides: &mut UniverseChannels<Option<u8>>, device: &Device, value: AttributeValue) -> bool {
+    let values = channel_values(device, value);
+    for (offset, value) in &values {
+        if let Some(channel) = device.channel_id(*offset) {
+            *overrides.get_mut(channel) = Some(*value);
+        }
+    }
+    !values.is_empty()
+}
+
+///Sets the attribute of `value` on all `devices` by writing into the overrides.
+///Devices, which don't have that attribute, are skipped.
+pub(super) fn apply(data: &mut CommonData, devices: &[DeviceId], value: AttributeValue) -> ApplyReport {
+    let mut report = ApplyReport::default();
+    for id in devices {
+        let Some((universe, _, device)) = data.devices.find(*id) else {
+            report.missing += 1;
+            continue;
+        };
+        let Ok(universe) = ux2::u15::try_from(universe) else { continue };
+        let overrides = &mut data.overrides.create_or_get_universe(universe).channels;
+        if apply_to_device(overrides, device, value) {
+            report.applied += 1;
+        } else {
+            report.skipped += 1;
+        }
+    }
+    report
+}
+
+///Returns the logical pan and tilt of `device`, if it has those attributes.
+///This undoes the calibration of `device`, so the result matches the values given to [`apply`].
+pub(super) fn position(overrides: &UniverseChannels<Option<u8>>, device: &Device) -> (Option<Position>, Option<Position>) {
+    let mut pan = None;
+    let mut tilt = None;
+    let calibration = device.get_position_calibration();
+    let value_of = |offset: usize| device.channel_id(offset).and_then(|channel|*overrides.get(channel));
+    for (offset, channel) in device.get_channels().iter().enumerate() {
+        let Action::SimpleAction(SimpleAction::VariableChannelAction(action)) = channel.get_action() else { continue };
+        let Some(axis) = position_axis(action) else { continue };
+        let resolved = action.resolve(device.get_variable_selection());
+        let range = resolved.range();
+        let coarse = value_of(offset);
+        let fine = channel.get_fine().and_then(|fine|value_of(usize::from(fine)));
+        let position = Position{
+            value: coarse.map(|coarse|{
+                let value = resolved.scale_from_range_u16(join_u16(coarse, fine.unwrap_or_default()));
+                calibration.uncalibrate(axis, value, range)
+            }),
+            range,
+        };
+        //swapping is its own inverse
+        match calibration.physical_axis(axis) {
+            PositionAxis::Pan => pan = pan.or(Some(position)),
+            PositionAxis::Tilt => tilt = tilt.or(Some(position)),
+        }
+    }
+    (pan, tilt)
+}
+
+///Removes all overrides of all channels of `devices`.
+pub(super) fn release(data: &mut CommonData, devices: &[DeviceId]) {
+    for id in devices {
+        let Some((universe, _, device)) = data.devices.find(*id) else { continue };
+        let Ok(universe) = ux2::u15::try_from(universe) else { continue };
+        let channels = device.get_channels().len();
+        let overrides = &mut data.overrides.create_or_get_universe(universe).channels;
+        for offset in 0..channels {
+            if let Some(channel) = device.channel_id(offset) {
+                *overrides.get_mut(channel) = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::artnet::fixture::Fixture;
+
+    fn dimmer_device() -> Option<Device> {
+        let fixture = Fixture::new(
+            Arc::from("Test"),
+            Arc::from("16-bit Dimmer"),
+            Arc::from("Test"),
+            Arc::new([
+                Channel::new_coarse(SimpleAction::IntensityMasterDimmer, 1),
+                Channel::new_fine(0),
+            ]),
+        );
+        Device::new_u16(Arc::from("Test"), 0, fixture, 0).ok()
+    }
+
+    #[test]
+    fn dimmer_writes_fine_channel() {
+        let device = dimmer_device();
+        assert!(device.is_some(), "the test device must be valid");
+        for (dimmer, expected) in [(0, vec![(0, 0), (1, 0)]), (u8::MAX, vec![(0, u8::MAX), (1, u8::MAX)]), (128, vec![(0, 128), (1, 128)])] {
+            assert_eq!(
+                device.as_ref().map(|device|channel_values(device, AttributeValue::Dimmer(dimmer))),
+                Some(expected),
+                "a dimmer of {dimmer} must set the coarse and the fine channel"
+            );
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use crate::app::popup::{get_id, popup_creator_raw, PopupFunc};
+use crate::artnet::fixture::{DeviceError, DeviceId};
+use crate::artnet::universe::{InsertError, UniverseDevices, Universes};
+use crate::fixturestore::FixtureStore;
+
+///A device, whose embedded fixture differs from the fixture with the same path and model in the fixture store.
+#[derive(Debug, Clone)]
+pub(super) struct OutdatedDevice{
+    universe: usize,
+    id: DeviceId,
+    name: Arc<str>,
+    model: Arc<str>,
+    device_revision: u32,
+    store_revision: u32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+pub(super) enum UpgradeError{
+    #[error("{0}")]
+    Device(#[from] DeviceError),
+    #[error("{0}")]
+    Insert(#[from] InsertError),
+}
+
+pub(super) fn find_outdated_devices(fixture_store: &FixtureStore, devices: &Universes<UniverseDevices>) -> Vec<OutdatedDevice> {
+    let mut outdated = Vec::new();
+    for (universe, devices) in devices.iter().enumerate() {
+        for device in devices {
+            if let Some(fixture) = fixture_store.find(device.fixture.get_path().as_ref(), device.fixture.get_model()) {
+                if *fixture != device.fixture {
+                    outdated.push(OutdatedDevice{
+                        universe,
+                        id: device.get_id(),
+                        name: device.name.clone(),
+                        model: device.fixture.get_model().clone(),
+                        device_revision: device.fixture.get_revision(),
+                        store_revision: fixture.get_revision(),
+                    });
+                }
+            }
+        }
+    }
+    outdated
+}
+
+///Replaces the fixture of all `outdated` devices with the fixture from the fixture store.
+///
+///The new channel span of every device gets validated the same way as when adding a new device.
+///Devices, that cannot be upgraded stay unchanged.
+pub(super) fn upgrade_devices(fixture_store: &FixtureStore, devices: &mut Universes<UniverseDevices>, outdated: &[OutdatedDevice]) -> Vec<(Arc<str>, UpgradeError)> {
+    let mut errors = Vec::new();
+    for outdated in outdated {
+        let Some((universe, index, _)) = devices.find(outdated.id) else { continue };
+        let Some(universe) = devices.get_mut(universe) else { continue };
+        let Some(device) = universe.iter().nth(index) else { continue };
+        let Some(fixture) = fixture_store.find(device.fixture.get_path().as_ref(), device.fixture.get_model()) else { continue };
+        let result = device.with_fixture(fixture.clone())
+            .map_err(UpgradeError::from)
+            .and_then(|device|universe.try_replace(index, device).map_err(UpgradeError::from));
+        if let Err(err) = result {
+            errors.push((outdated.name.clone(), err));
+        }
+    }
+    errors
+}
+
+///Creates a popup, which lists the `outdated` devices and offers to upgrade them.
+pub(super) fn upgrade_popup(outdated: Vec<OutdatedDevice>) -> Box<PopupFunc> {
+    let mut result: Option<Vec<(Arc<str>, UpgradeError)>> = None;
+    let grid_id = get_id();
+    popup_creator_raw("Fixture Definitions Changed", move |app, ui|{
+        ui.label("The following devices use a different version of their fixture than the fixture store:");
+        egui::Grid::new(grid_id)
+            .num_columns(4)
+            .show(ui, |ui|{
+                ui.label("Universe");
+                ui.label("Device");
+                ui.label("Fixture");
+                ui.label("Revision (Device → Store)");
+                ui.end_row();
+                for device in &outdated {
+                    ui.label(device.universe.to_string());
+                    ui.label(device.name.as_ref());
+                    ui.label(device.model.as_ref());
+                    ui.label(format!("{} → {}", device.device_revision, device.store_revision));
+                    ui.end_row();
+                }
+            });
+        match &result {
+            None => {
+                if ui.button("Upgrade All").clicked() {
+                    result = Some(upgrade_devices(
+                        &app.serializable_app_data.fixture_store,
+                        &mut app.serializable_app_data.data.devices,
+                        outdated.as_slice()
+                    ));
+                }
+            },
+            Some(errors) if errors.is_empty() => {
+                ui.label("All devices have been upgraded.");
+            },
+            Some(errors) => {
+                ui.label("The following devices could not be upgraded and were left unchanged:");
+                for (name, err) in errors {
+                    ui.label(format!("{name}: {err}"));
+                }
+            },
+        }
+    })
+}
@@ -1,3 +1,5 @@
 pub mod fixture;
 pub mod channel;
-pub mod universe;
\ No newline at end of file
+pub mod universe;
+pub mod group;
+pub mod mixer;
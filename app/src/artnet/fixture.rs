@@ -1,17 +1,150 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use serde_derive::{Deserialize, Serialize};
-use channel::Channel;
+use channel::{Action, Channel, SimpleAction};
 
+pub mod calibration;
 pub mod channel;
+pub mod curve;
 pub mod variables;
+pub mod validate;
 
+///One DMX mode (sometimes also called personality) of a fixture.
+///Real fixtures often offer e.g. a 7ch, 13ch and 16ch mode, which all map the channels differently.
 #[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct FixtureMode{
+    name: Arc<str>,
+    channels: Arc<[Channel]>,
+}
+
+impl FixtureMode {
+    #[inline]
+    pub const fn new(name: Arc<str>, channels: Arc<[Channel]>) -> Self {
+        Self{
+            name,
+            channels,
+        }
+    }
+
+    ///Creates a mode, which is named after the amount of channels it has (e.g. `13ch`).
+    pub fn from_channels(channels: Arc<[Channel]>) -> Self {
+        Self::new(Arc::from(format!("{}ch", channels.len())), channels)
+    }
+
+    #[inline]
+    pub const fn get_name(&self) -> &Arc<str> {
+        &self.name
+    }
+
+    #[inline]
+    pub const fn get_channels(&self) -> &Arc<[Channel]> {
+        &self.channels
+    }
+
+    ///Maps the channels of this mode to the channels of `other`, which control the same attribute.
+    ///Returns pairs of (offset in `self`, offset in `other`). Every channel is mapped at most once.
+    pub fn map_channels(&self, other: &Self) -> Vec<(usize, usize)> {
+        let mut used = HashSet::new();
+        let mut mapping = Vec::new();
+        for (offset, channel) in self.channels.iter().enumerate() {
+            if matches!(channel.get_action(), Action::Fine(_) | Action::SimpleAction(SimpleAction::NoOp)) {
+                continue;
+            }
+            let Some((other_offset, other_channel)) = other.channels.iter()
+                .enumerate()
+                .find(|(i, candidate)|!used.contains(i) && channel.get_action().same_attribute(candidate.get_action()))
+                else { continue };
+            used.insert(other_offset);
+            mapping.push((offset, other_offset));
+            if let (Some(fine), Some(other_fine)) = (channel.get_fine(), other_channel.get_fine()) {
+                if used.insert(usize::from(other_fine)) {
+                    mapping.push((usize::from(fine), usize::from(other_fine)));
+                }
+            }
+        }
+        mapping
+    }
+
+    ///Human readable name of the channel at `offset`.
+    ///Fine channels are named after their coarse channel.
+    pub fn channel_name(&self, offset: usize) -> Option<String> {
+        let channel = self.channels.get(offset)?;
+        Some(match channel.get_coarse().and_then(|coarse|self.channels.get(usize::from(coarse))) {
+            Some(coarse) => format!("{} Fine", coarse.get_action()),
+            None => channel.get_action().to_string(),
+        })
+    }
+
+    ///The category of the attribute, that the channel at `offset` controls.
+    ///Fine channels belong to the category of their coarse channel, selections to the first range, that does something.
+    pub fn channel_category(&self, offset: usize) -> Option<channel::AttributeCategory> {
+        let channel = self.channels.get(offset)?;
+        Some(match channel.get_action() {
+            Action::SimpleAction(action) => action.category(),
+            Action::Selection(ranges) => ranges.iter()
+                .map(|range|range.get_action().category())
+                .find(|category|*category != channel::AttributeCategory::None)
+                .unwrap_or(channel::AttributeCategory::None),
+            Action::Fine(coarse) => self.channels.get(usize::from(*coarse))
+                .and_then(|coarse|match coarse.get_action() {
+                    Action::SimpleAction(action) => Some(action.category()),
+                    Action::Selection(_) | Action::Fine(_) => None,
+                })
+                .unwrap_or(channel::AttributeCategory::None),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[serde(from = "FixtureRepr")]
 pub struct Fixture{
     manufacturer: Arc<str>,
     extra_path: Arc<[Arc<str>]>,
     model: Arc<str>,
     r#type: Arc<str>,
-    channels: Arc<[Channel]>,
+    ///invariant: this always contains at least one mode.
+    modes: Arc<[FixtureMode]>,
+    ///Gets increased, whenever the definition of this fixture changes.
+    ///This allows detecting devices, which still use an older copy of this fixture.
+    revision: u32,
+    ///Applied to all intensity channels. Devices may override this.
+    response_curve: curve::ResponseCurve,
+}
+
+///This is only used for deserializing a [`Fixture`].
+///
+///Older projects stored a single `channels` list per fixture instead of a list of `modes`.
+///Such a channel list gets converted into a single mode.
+#[derive(Debug, Deserialize)]
+#[serde(rename = "Fixture")]
+struct FixtureRepr{
+    manufacturer: Arc<str>,
+    extra_path: Arc<[Arc<str>]>,
+    model: Arc<str>,
+    r#type: Arc<str>,
+    #[serde(default)]
+    modes: Vec<FixtureMode>,
+    #[serde(default)]
+    channels: Vec<Channel>,
+    #[serde(default)]
+    revision: u32,
+    #[serde(default)]
+    response_curve: curve::ResponseCurve,
+}
+
+impl From<FixtureRepr> for Fixture {
+    fn from(value: FixtureRepr) -> Self {
+        let modes = if value.modes.is_empty() {
+            Arc::from([FixtureMode::from_channels(Arc::from(value.channels))])
+        } else {
+            Arc::from(value.modes)
+        };
+        Self::with_modes(value.manufacturer, value.extra_path, value.model, value.r#type, modes)
+            .with_revision(value.revision)
+            .with_response_curve(value.response_curve)
+    }
 }
 
 impl Fixture {
@@ -26,13 +159,30 @@ impl Fixture {
         )
     }
     #[inline]
-    pub const fn new_path(manufacturer: Arc<str>, extra_path: Arc<[Arc<str>]>, model: Arc<str>, r#type: Arc<str>, channels: Arc<[Channel]>) -> Self {
+    pub fn new_path(manufacturer: Arc<str>, extra_path: Arc<[Arc<str>]>, model: Arc<str>, r#type: Arc<str>, channels: Arc<[Channel]>) -> Self {
+        Self::with_modes(
+            manufacturer,
+            extra_path,
+            model,
+            r#type,
+            Arc::from([FixtureMode::from_channels(channels)]),
+        )
+    }
+    ///If `modes` is empty, a single mode without any channels will be created.
+    pub fn with_modes(manufacturer: Arc<str>, extra_path: Arc<[Arc<str>]>, model: Arc<str>, r#type: Arc<str>, modes: Arc<[FixtureMode]>) -> Self {
+        let modes = if modes.is_empty() {
+            Arc::from([FixtureMode::from_channels(Arc::from([]))])
+        } else {
+            modes
+        };
         Self {
             manufacturer,
             extra_path,
             model,
             r#type,
-            channels,
+            modes,
+            revision: 0,
+            response_curve: curve::ResponseCurve::Linear,
         }
     }
 
@@ -51,9 +201,43 @@ impl Fixture {
         &self.r#type
     }
 
+    #[must_use]
+    pub fn with_model(mut self, model: Arc<str>) -> Self {
+        self.model = model;
+        self
+    }
+
     #[inline]
-    pub const fn get_channels(&self) -> &Arc<[Channel]> {
-        &self.channels
+    pub const fn get_revision(&self) -> u32 {
+        self.revision
+    }
+
+    #[must_use]
+    pub const fn with_revision(mut self, revision: u32) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    ///The response curve of the intensity channels of this fixture. See [`Device::get_response_curve`].
+    #[inline]
+    pub const fn get_response_curve(&self) -> &curve::ResponseCurve {
+        &self.response_curve
+    }
+
+    #[must_use]
+    pub fn with_response_curve(mut self, response_curve: curve::ResponseCurve) -> Self {
+        self.response_curve = response_curve;
+        self
+    }
+
+    #[inline]
+    pub const fn get_modes(&self) -> &Arc<[FixtureMode]> {
+        &self.modes
+    }
+
+    #[inline]
+    pub fn get_mode(&self, mode: usize) -> Option<&FixtureMode> {
+        self.modes.get(mode)
     }
 
     #[must_use]
@@ -67,13 +251,68 @@ impl Fixture {
     }
 }
 
+///The next id, that [`DeviceId::next`] hands out.
+static NEXT_DEVICE_ID: AtomicU64 = AtomicU64::new(1);
+
+///Identifies a [`Device`], independent of where it is patched.
+///The id stays the same, when the device gets moved or its fixture gets replaced and is stored in the project.
+///
+///Devices from projects, which didn't store ids yet, are [`DeviceId::UNASSIGNED`] until
+///[`Universes::assign_device_ids`](crate::artnet::universe::Universes::assign_device_ids) is called.
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DeviceId(u64);
+
+impl DeviceId {
+    pub const UNASSIGNED: Self = Self(0);
+
+    fn next() -> Self {
+        Self(NEXT_DEVICE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[inline]
+    pub const fn is_assigned(self) -> bool {
+        self.0 != Self::UNASSIGNED.0
+    }
+
+    #[must_use]
+    pub(crate) const fn successor(self) -> Self {
+        Self(self.0.saturating_add(1))
+    }
+
+    ///Makes sure, that all ids handed out from now on are bigger than `self`.
+    ///This needs to be called for the highest id of a loaded project.
+    pub(crate) fn reserve(self) {
+        NEXT_DEVICE_ID.fetch_max(self.0.saturating_add(1), Ordering::Relaxed);
+    }
+}
+
+impl Display for DeviceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Device {
+    #[serde(default)]
+    id: DeviceId,
     pub name: Arc<str>,
-    ///`self.start_id + self.fixture.channels.len()` should always be inside an u9.
+    ///`self.start_id + self.get_channels().len()` should always be inside an u9.
     start_id: ux2::u9,
     end_id: ux2::u9,
     pub fixture: Fixture,
+    ///Index of the [`FixtureMode`] in `self.fixture`, that this device is patched in.
+    #[serde(default)]
+    mode: usize,
+    ///Which options of the [`VariableChannelAction`](variables::VariableChannelAction)s of the fixture this device uses
+    ///(e.g. the pan range, that was configured for this device).
+    #[serde(default)]
+    variable_selection: variables::VariableSelection,
+    #[serde(default)]
+    position_calibration: calibration::PositionCalibration,
+    ///Replaces the response curve of the fixture for this device, if set.
+    #[serde(default)]
+    response_curve: Option<curve::ResponseCurve>,
 }
 
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, thiserror::Error)]
@@ -82,25 +321,43 @@ pub enum ChannelError{
     ChannelIdTooHigh(u16)
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum DeviceError{
+    #[error("The device does not fit into a universe: {0}")]
+    ChannelIdTooHigh(#[from] ux2::TryFromIntError),
+    #[error("The fixture has no mode with the index {0}.")]
+    InvalidMode(usize),
+}
+
 impl Device{
-    pub fn new_u16(name: Arc<str>, start_id: u16, fixture: Fixture) -> Result<Self, ux2::TryFromIntError> {
-        let u9_start_id = ux2::u9::try_from(start_id)?;
-        Ok(Self{
-            name,
-            start_id: u9_start_id,
-            end_id: ux2::u9::try_from(start_id as usize + fixture.channels.len())?,
-            fixture,
-        })
+    pub fn new_u16(name: Arc<str>, start_id: u16, fixture: Fixture, mode: usize) -> Result<Self, DeviceError> {
+        Self::new(name, ux2::u9::try_from(start_id)?, fixture, mode)
     }
-    pub fn new(name: Arc<str>, start_id: ux2::u9, fixture: Fixture) -> Result<Self, ux2::TryFromIntError> {
+    pub fn new(name: Arc<str>, start_id: ux2::u9, fixture: Fixture, mode: usize) -> Result<Self, DeviceError> {
+        let channels = fixture.get_mode(mode).ok_or(DeviceError::InvalidMode(mode))?.get_channels().len();
         Ok(Self{
+            id: DeviceId::next(),
             name,
             start_id,
-            end_id: ux2::u9::try_from(<ux2::u9 as Into<usize>>::into(start_id) + fixture.channels.len())?,
+            end_id: ux2::u9::try_from(<ux2::u9 as Into<usize>>::into(start_id) + channels)?,
             fixture,
+            mode,
+            variable_selection: variables::VariableSelection::default(),
+            position_calibration: calibration::PositionCalibration::default(),
+            response_curve: None,
         })
     }
 
+    #[inline]
+    pub const fn get_id(&self) -> DeviceId {
+        self.id
+    }
+
+    ///Only meant for repairing missing or duplicate ids after loading a project.
+    pub(crate) fn set_id(&mut self, id: DeviceId) {
+        self.id = id;
+    }
+
     #[inline]
     pub const fn start_channel(&self) -> ux2::u9 {
         self.start_id
@@ -110,4 +367,92 @@ impl Device{
     pub const fn end_channel(&self) -> ux2::u9 {
         self.end_id
     }
+
+    ///Returns the absolute channel in the universe of the channel at `offset` of this device.
+    pub fn channel_id(&self, offset: usize) -> Option<ux2::u9> {
+        ux2::u9::try_from(usize::from(self.start_id) + offset).ok()
+    }
+
+    #[inline]
+    pub const fn mode_index(&self) -> usize {
+        self.mode
+    }
+
+    ///Returns the [`FixtureMode`] this device is patched in.
+    ///
+    ///Falls back to the first mode of the fixture, if the stored mode index is invalid.
+    #[allow(clippy::indexing_slicing)] //Fixture guarantees, that there is always at least one mode
+    pub fn get_mode(&self) -> &FixtureMode {
+        self.fixture.get_mode(self.mode).unwrap_or(&self.fixture.get_modes()[0])
+    }
+
+    #[inline]
+    pub fn get_channels(&self) -> &Arc<[Channel]> {
+        self.get_mode().get_channels()
+    }
+
+    ///Selects, which of the options of the [`VariableChannelAction`](variables::VariableChannelAction)s of the fixture this device uses.
+    #[inline]
+    pub const fn get_variable_selection(&self) -> variables::VariableSelection {
+        self.variable_selection
+    }
+
+    #[inline]
+    pub fn set_variable_selection(&mut self, variable_selection: variables::VariableSelection) {
+        self.variable_selection = variable_selection;
+    }
+
+    ///How the pan/tilt of this device is mounted. See [`calibration::PositionCalibration`].
+    #[inline]
+    pub const fn get_position_calibration(&self) -> calibration::PositionCalibration {
+        self.position_calibration
+    }
+
+    #[inline]
+    pub fn set_position_calibration(&mut self, position_calibration: calibration::PositionCalibration) {
+        self.position_calibration = position_calibration;
+    }
+
+    ///The response curve of the intensity channels of this device.
+    ///This is the override of this device, if there is one and the curve of the fixture otherwise.
+    pub fn get_response_curve(&self) -> &curve::ResponseCurve {
+        self.response_curve.as_ref().unwrap_or_else(||self.fixture.get_response_curve())
+    }
+
+    #[inline]
+    pub const fn get_response_curve_override(&self) -> Option<&curve::ResponseCurve> {
+        self.response_curve.as_ref()
+    }
+
+    #[inline]
+    pub fn set_response_curve_override(&mut self, response_curve: Option<curve::ResponseCurve>) {
+        self.response_curve = response_curve;
+    }
+
+    ///Creates a copy of this device with the same name, id and settings (e.g. the calibration), that is patched at `start_id` in `mode` of `fixture`.
+    pub fn repatched(&self, start_id: ux2::u9, fixture: Fixture, mode: usize) -> Result<Self, DeviceError> {
+        let mut device = Self::new(self.name.clone(), start_id, fixture, mode)?;
+        device.id = self.id;
+        device.variable_selection = self.variable_selection;
+        device.position_calibration = self.position_calibration;
+        device.response_curve = self.response_curve.clone();
+        Ok(device)
+    }
+
+    ///Creates a copy of this device, that starts at `start_id`.
+    pub fn with_start_channel(&self, start_id: ux2::u9) -> Result<Self, DeviceError> {
+        self.repatched(start_id, self.fixture.clone(), self.mode)
+    }
+
+    ///Creates a copy of this device with the same name and start channel, but using `fixture`.
+    ///
+    ///The new device is patched in the mode of `fixture`, that has the same name as the current mode.
+    ///If there is no such mode, the mode with the same index is used.
+    pub fn with_fixture(&self, fixture: Fixture) -> Result<Self, DeviceError> {
+        let mode_name = self.get_mode().get_name();
+        let mode = fixture.get_modes().iter()
+            .position(|mode|mode.get_name() == mode_name)
+            .unwrap_or(self.mode);
+        self.repatched(self.start_id, fixture, mode)
+    }
 }
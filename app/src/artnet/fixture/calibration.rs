@@ -0,0 +1,150 @@
+use serde_derive::{Deserialize, Serialize};
+use crate::degree::deg_to_microarcseconds;
+
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum PositionAxis{
+    Pan,
+    Tilt,
+}
+
+impl PositionAxis {
+    #[must_use]
+    pub const fn other(self) -> Self {
+        match self {
+            Self::Pan => Self::Tilt,
+            Self::Tilt => Self::Pan,
+        }
+    }
+}
+
+///Corrects how a device is mounted, so that the same pan/tilt makes all devices of a rig point the same way
+///(e.g. the mirrored movers on the other side of a stage).
+///
+///Positions in the programmer are logical positions. They get converted to the physical position of the device like this:
+///1. If `swap` is set, logical pan controls the physical tilt and the other way around.
+///2. The offset of the physical axis is added. The result is clamped to the range of the axis.
+///3. If the physical axis is inverted, the position is mirrored inside the range of the axis.
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PositionCalibration{
+    pub invert_pan: bool,
+    pub invert_tilt: bool,
+    pub swap: bool,
+    ///in degrees
+    pub pan_offset: i32,
+    ///in degrees
+    pub tilt_offset: i32,
+}
+
+impl PositionCalibration {
+    ///Returns the physical axis, that is controlled by the logical `axis`.
+    #[inline]
+    pub const fn physical_axis(&self, axis: PositionAxis) -> PositionAxis {
+        if self.swap {
+            axis.other()
+        } else {
+            axis
+        }
+    }
+
+    ///Returns the offset in micro-arc-seconds and whether the physical `axis` is inverted.
+    fn axis_settings(&self, axis: PositionAxis) -> (i128, bool) {
+        let (offset, invert) = match axis {
+            PositionAxis::Pan => (self.pan_offset, self.invert_pan),
+            PositionAxis::Tilt => (self.tilt_offset, self.invert_tilt),
+        };
+        (i128::from(offset) * i128::from(deg_to_microarcseconds(1)), invert)
+    }
+
+    fn clamp(value: i128, range: u64) -> u64 {
+        u64::try_from(value.clamp(0, i128::from(range))).unwrap_or_default()
+    }
+
+    ///Converts the logical position `value` (in micro-arc-seconds) into the position of the physical `axis`, which has a total range of `range`.
+    ///`axis` is the physical axis, see [`Self::physical_axis`].
+    pub fn calibrate(&self, axis: PositionAxis, value: u64, range: u64) -> u64 {
+        let (offset, invert) = self.axis_settings(axis);
+        let value = Self::clamp(i128::from(value) + offset, range);
+        if invert {
+            range - value
+        } else {
+            value
+        }
+    }
+
+    ///The inverse of [`Self::calibrate`] (except for positions, that got clamped).
+    pub fn uncalibrate(&self, axis: PositionAxis, value: u64, range: u64) -> u64 {
+        let (offset, invert) = self.axis_settings(axis);
+        let value = value.min(range);
+        let value = if invert {
+            range - value
+        } else {
+            value
+        };
+        Self::clamp(i128::from(value) - offset, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGE: u64 = deg_to_microarcseconds(540);
+    const DEG: u64 = deg_to_microarcseconds(1);
+
+    #[test]
+    fn round_trip() {
+        let calibration = PositionCalibration{
+            invert_pan: true,
+            invert_tilt: false,
+            swap: true,
+            pan_offset: 20,
+            tilt_offset: -30,
+        };
+        for axis in [PositionAxis::Pan, PositionAxis::Tilt] {
+            let physical = calibration.physical_axis(axis);
+            for value in [DEG * 40, DEG * 100, DEG * 270, DEG * 500] {
+                let calibrated = calibration.calibrate(physical, value, RANGE);
+                assert_eq!(calibration.uncalibrate(physical, calibrated, RANGE), value, "uncalibrate must invert calibrate for {axis:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn offset_and_invert() {
+        let calibration = PositionCalibration{
+            invert_pan: true,
+            pan_offset: 10,
+            ..PositionCalibration::default()
+        };
+        assert_eq!(calibration.calibrate(PositionAxis::Pan, DEG * 100, RANGE), RANGE - DEG * 110, "the offset must be added before inverting");
+        assert_eq!(calibration.calibrate(PositionAxis::Tilt, DEG * 100, RANGE), DEG * 100, "other axes must stay unchanged");
+    }
+
+    #[test]
+    fn clamping() {
+        let negative = PositionCalibration{
+            pan_offset: -90,
+            ..PositionCalibration::default()
+        };
+        assert_eq!(negative.calibrate(PositionAxis::Pan, DEG * 10, RANGE), 0, "negative positions must be clamped to 0");
+        assert_eq!(negative.uncalibrate(PositionAxis::Pan, RANGE, RANGE), RANGE, "uncalibrated positions must be clamped to the range");
+        let over_range = PositionCalibration{
+            pan_offset: 90,
+            invert_pan: true,
+            ..PositionCalibration::default()
+        };
+        assert_eq!(over_range.calibrate(PositionAxis::Pan, RANGE - DEG * 10, RANGE), 0, "positions over the range must be clamped, then inverted");
+        assert_eq!(over_range.uncalibrate(PositionAxis::Pan, RANGE, RANGE), 0, "uncalibrated negative positions must be clamped to 0");
+        assert_eq!(PositionCalibration::default().calibrate(PositionAxis::Tilt, RANGE * 2, RANGE), RANGE, "values over the range must be clamped");
+    }
+
+    #[test]
+    fn physical_axis() {
+        let mut calibration = PositionCalibration::default();
+        assert_eq!(calibration.physical_axis(PositionAxis::Pan), PositionAxis::Pan, "without swap pan must stay pan");
+        assert_eq!(calibration.physical_axis(PositionAxis::Tilt), PositionAxis::Tilt, "without swap tilt must stay tilt");
+        calibration.swap = true;
+        assert_eq!(calibration.physical_axis(PositionAxis::Pan), PositionAxis::Tilt, "with swap pan must control tilt");
+        assert_eq!(calibration.physical_axis(PositionAxis::Tilt), PositionAxis::Pan, "with swap tilt must control pan");
+    }
+}
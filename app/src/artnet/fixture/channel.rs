@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use serde_derive::{Deserialize, Serialize};
 use crate::artnet::fixture::variables::{VariableChannelAction, VariableSelection};
@@ -5,18 +6,78 @@ use crate::artnet::fixture::variables::{VariableChannelAction, VariableSelection
 #[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Channel{
     action: Action,
+    ///If set, this channel is the coarse (most significant) byte of a 16-bit attribute.
+    ///The contained value is the offset of the fine (least significant) channel,
+    /// relative to the first channel of the fixture mode.
+    #[serde(default)]
+    fine: Option<u16>,
 }
 
 impl Channel{
     pub const fn new(action: Action) -> Self {
         Self{
-            action
+            action,
+            fine: None,
         }
     }
     pub const fn new_simple(action: SimpleAction) -> Self {
         Self::new(Action::SimpleAction(action))
     }
+    ///Creates the coarse channel of a 16-bit attribute, whose fine channel is at the offset `fine`.
+    pub const fn new_coarse(action: SimpleAction, fine: u16) -> Self {
+        Self{
+            action: Action::SimpleAction(action),
+            fine: Some(fine),
+        }
+    }
+    ///Creates the fine channel of a 16-bit attribute, whose coarse channel is at the offset `coarse`.
+    pub const fn new_fine(coarse: u16) -> Self {
+        Self::new(Action::Fine(coarse))
+    }
+
+    #[inline]
+    pub const fn get_action(&self) -> &Action {
+        &self.action
+    }
+
+    ///Offset of the fine channel, if this is the coarse channel of a 16-bit attribute.
+    #[inline]
+    pub const fn get_fine(&self) -> Option<u16> {
+        self.fine
+    }
+
+    ///Offset of the coarse channel, if this is the fine channel of a 16-bit attribute.
+    #[inline]
+    pub const fn get_coarse(&self) -> Option<u16> {
+        match self.action {
+            Action::Fine(coarse) => Some(coarse),
+            Action::SimpleAction(_) |
+            Action::Selection(_) => None,
+        }
+    }
+
+    ///Splits the 16-bit `value` of this channel, which is at `offset` in its fixture mode, into (offset, dmx value) pairs.
+    ///The coarse byte is written to `offset` and the fine byte to the fine channel.
+    ///Without a fine channel only the coarse byte is returned.
+    pub fn u16_values(&self, offset: usize, value: u16) -> Vec<(usize, u8)> {
+        let (coarse, fine) = split_u16(value);
+        core::iter::once((offset, coarse))
+            .chain(self.fine.map(|offset|(usize::from(offset), fine)))
+            .collect()
+    }
+}
 
+///Splits the value of a 16-bit attribute into the values of it's coarse and fine dmx channel.
+#[inline]
+pub const fn split_u16(value: u16) -> (u8, u8) {
+    let [coarse, fine] = value.to_be_bytes();
+    (coarse, fine)
+}
+
+///Combines the values of the coarse and fine dmx channel of a 16-bit attribute.
+#[inline]
+pub const fn join_u16(coarse: u8, fine: u8) -> u16 {
+    u16::from_be_bytes([coarse, fine])
 }
 ///The contained data represents one distinct range of a channel.
 ///
@@ -29,12 +90,58 @@ impl Channel{
 /// Please note that it should be expected, that `start<end` is possible and should be respected.
 /// In this case the lower value is the start of the range in the actual dmx output range,
 /// but the `action` gets stronger with a lower value.
+///
+/// `slot` optionally describes what this range selects on e.g. a GOBO or color wheel.
 #[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Range{
     continuous: bool,
     start: u8,
     end: u8,
-    action: SimpleAction
+    action: SimpleAction,
+    #[serde(default)]
+    slot: Option<Slot>,
+}
+
+///A named slot of e.g. a GOBO or color wheel.
+#[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Slot{
+    name: Arc<str>,
+    ///Path or uri of an image, that shows this slot (e.g. the GOBO pattern).
+    #[serde(default)]
+    image: Option<Arc<str>>,
+    ///Approximate color of this slot in srgb.
+    ///For color wheels this is the color of the filter, for GOBOs it may be used as a swatch.
+    #[serde(default)]
+    color: Option<[u8;3]>,
+}
+
+impl Slot{
+    ///A slot without image or color. Images can only be set in imported fixture definitions.
+    pub const fn new(name: Arc<str>) -> Self {
+        Self{
+            name,
+            image: None,
+            color: None,
+        }
+    }
+    #[must_use]
+    pub const fn with_color(mut self, color: [u8;3]) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    #[inline]
+    pub const fn get_name(&self) -> &Arc<str> {
+        &self.name
+    }
+    #[inline]
+    pub const fn get_image(&self) -> Option<&Arc<str>> {
+        self.image.as_ref()
+    }
+    #[inline]
+    pub const fn get_color(&self) -> Option<[u8;3]> {
+        self.color
+    }
 }
 
 impl Range{
@@ -48,9 +155,31 @@ impl Range{
             start,
             end,
             action,
+            slot: None,
         }
     }
 
+    #[must_use]
+    pub fn with_slot(mut self, slot: Slot) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    #[inline]
+    pub const fn get_action(&self) -> &SimpleAction {
+        &self.action
+    }
+
+    #[inline]
+    pub const fn get_slot(&self) -> Option<&Slot> {
+        self.slot.as_ref()
+    }
+
+    ///Name of this range. This is the slot name, if there is one and the action otherwise.
+    pub fn get_name(&self) -> String {
+        self.slot.as_ref().map_or_else(|| self.action.to_string(), |slot|slot.name.to_string())
+    }
+
     #[inline]
     pub const fn is_continuous(&self) -> bool {
         self.continuous || self.action.is_continuous()
@@ -121,11 +250,75 @@ impl Range{
     }
 }
 
+impl Display for Range {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}: {}", self.start, self.end, self.get_name())
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Action {
     SimpleAction(SimpleAction),
     ///The people instantiating this are responsible for putting sensible data in here.
-    Selection(Arc<[Range]>)
+    Selection(Arc<[Range]>),
+    ///The fine (least significant) byte of a 16-bit attribute.
+    ///The contained value is the offset of the coarse channel, relative to the first channel of the fixture mode.
+    Fine(u16),
+}
+
+impl Action {
+    ///True, if both actions control the same attribute.
+    ///
+    ///Unlike `==` this ignores e.g. the range of a pan channel or the exact values of a selection.
+    pub fn same_attribute(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::SimpleAction(SimpleAction::VariableChannelAction(a)),
+                Self::SimpleAction(SimpleAction::VariableChannelAction(b)),
+            ) => core::mem::discriminant(a) == core::mem::discriminant(b),
+            (Self::Selection(a), Self::Selection(b)) => {
+                let actions = |ranges: &[Range]| {
+                    let mut actions = ranges.iter()
+                        .map(Range::get_action)
+                        .filter(|action|!matches!(action, SimpleAction::NoOp))
+                        .collect::<Vec<_>>();
+                    actions.sort();
+                    actions.dedup();
+                    actions
+                };
+                actions(a) == actions(b)
+            },
+            (Self::Fine(_), Self::Fine(_)) => false,
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SimpleAction(action) => write!(f, "{action}"),
+            Self::Selection(ranges) => {
+                let mut actions = ranges.iter()
+                    .map(|range|&range.action)
+                    .filter(|action|!matches!(action, SimpleAction::NoOp))
+                    .collect::<Vec<_>>();
+                actions.sort();
+                actions.dedup();
+                if actions.is_empty() {
+                    return write!(f, "{}", SimpleAction::NoOp);
+                }
+                for (i, action) in actions.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " / ")?;
+                    }
+                    write!(f, "{action}")?;
+                }
+                Ok(())
+            },
+            Self::Fine(coarse) => write!(f, "Fine of Channel {}", u32::from(*coarse) + 1),
+        }
+    }
 }
 
 ///What does this channel Control?
@@ -143,6 +336,26 @@ pub enum SimpleAction {
     BeamZoom,
     IntensityMasterDimmer,
     IntensityColor(Color),
+    Beam(Beam),
+    ColorWheel,
+    ///Color temperature orange (CTO) correction
+    ColorTemperature,
+    Laser(Laser),
+    Control(Control),
+    Macro,
+}
+
+///Rough grouping of [`SimpleAction`]s.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum AttributeCategory {
+    None,
+    Position,
+    Intensity,
+    Color,
+    Beam,
+    Gobo,
+    Laser,
+    Control,
 }
 
 impl SimpleAction {
@@ -152,7 +365,41 @@ impl SimpleAction {
     }
     ///True, if different values produce a different effect
     pub const fn is_continuous(&self) -> bool {
-        !matches!(self, Self::GOBOSelection | Self::NoOp)
+        !matches!(self, Self::GOBOSelection | Self::NoOp | Self::ColorWheel | Self::Control(Control::Reset | Control::Lamp) | Self::Macro)
+    }
+    ///True, if this channel controls how much light is emitted (e.g. a dimmer or the red emitter of an RGB fixture).
+    ///Only these channels get scaled by submasters and response curves.
+    pub const fn is_intensity(&self) -> bool {
+        matches!(self, Self::IntensityMasterDimmer | Self::IntensityColor(_))
+    }
+    ///True, if this attribute may be split into a coarse and a fine channel.
+    pub const fn supports_fine(&self) -> bool {
+        matches!(self,
+            Self::VariableChannelAction(VariableChannelAction::PositionPan(_) | VariableChannelAction::PositionTilt(_)) |
+            Self::IntensityMasterDimmer |
+            Self::BeamZoom
+        )
+    }
+    ///The group, that this attribute belongs to. Used to organize channels in the ui.
+    pub const fn category(&self) -> AttributeCategory {
+        match self {
+            Self::NoOp => AttributeCategory::None,
+            Self::VariableChannelAction(_) => AttributeCategory::Position,
+            Self::IntensityMasterDimmer => AttributeCategory::Intensity,
+            Self::IntensityColor(_) |
+            Self::ColorWheel |
+            Self::ColorTemperature => AttributeCategory::Color,
+            Self::Strobo |
+            Self::BeamZoom |
+            Self::Beam(_) => AttributeCategory::Beam,
+            Self::SpinRight |
+            Self::SpinLeft |
+            Self::GOBOSelection => AttributeCategory::Gobo,
+            Self::Laser(_) => AttributeCategory::Laser,
+            Self::Speed |
+            Self::Control(_) |
+            Self::Macro => AttributeCategory::Control,
+        }
     }
 
     #[allow(clippy::cast_possible_truncation)] //yes, we want this here
@@ -168,18 +415,103 @@ impl SimpleAction {
             Self::GOBOSelection |
             Self::BeamZoom |
             Self::IntensityMasterDimmer |
-            Self::IntensityColor(_)
+            Self::IntensityColor(_) |
+            Self::Beam(_) |
+            Self::ColorWheel |
+            Self::ColorTemperature |
+            Self::Laser(_) |
+            Self::Control(_) |
+            Self::Macro
                 => input as u8
         }
     }
 }
 
+impl Display for SimpleAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoOp => write!(f, "No Function"),
+            Self::VariableChannelAction(var) => write!(f, "{var}"),
+            Self::Speed => write!(f, "Speed"),
+            Self::Strobo => write!(f, "Strobe"),
+            Self::SpinRight => write!(f, "Spin Right"),
+            Self::SpinLeft => write!(f, "Spin Left"),
+            Self::GOBOSelection => write!(f, "GOBO"),
+            Self::BeamZoom => write!(f, "Zoom"),
+            Self::IntensityMasterDimmer => write!(f, "Dimmer"),
+            Self::IntensityColor(color) => write!(f, "{color}"),
+            Self::Beam(beam) => write!(f, "{beam}"),
+            Self::ColorWheel => write!(f, "Color Wheel"),
+            Self::ColorTemperature => write!(f, "CTO"),
+            Self::Laser(laser) => write!(f, "Laser {laser}"),
+            Self::Control(control) => write!(f, "{control}"),
+            Self::Macro => write!(f, "Macro"),
+        }
+    }
+}
+
+impl Display for AttributeCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Position => write!(f, "Position"),
+            Self::Intensity => write!(f, "Intensity"),
+            Self::Color => write!(f, "Color"),
+            Self::Beam => write!(f, "Beam"),
+            Self::Gobo => write!(f, "GOBO"),
+            Self::Laser => write!(f, "Laser"),
+            Self::Control => write!(f, "Control"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Beam{ Focus, Iris, Prism, PrismRotation, Frost }
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Laser{ Width, Flatness, BeamCount, Thickness, Length }
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Control{ Reset, Lamp, MovementSpeed, Function }
+
+impl Display for Beam {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Focus => write!(f, "Focus"),
+            Self::Iris => write!(f, "Iris"),
+            Self::Prism => write!(f, "Prism"),
+            Self::PrismRotation => write!(f, "Prism Rotation"),
+            Self::Frost => write!(f, "Frost"),
+        }
+    }
+}
+impl Display for Laser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Width => write!(f, "Width"),
+            Self::Flatness => write!(f, "Flatness"),
+            Self::BeamCount => write!(f, "Beam Count"),
+            Self::Thickness => write!(f, "Beam Thickness"),
+            Self::Length => write!(f, "Length"),
+        }
+    }
+}
+impl Display for Control {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reset => write!(f, "Reset"),
+            Self::Lamp => write!(f, "Lamp Control"),
+            Self::MovementSpeed => write!(f, "Movement Speed"),
+            Self::Function => write!(f, "Function"),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Color{
     Rgb(ColorRGB),
     Hsv(ColorHSV),
     Hsl(ColorHSL),
     Hsi(ColorHSI),
+    Single(ColorSingle),
 }
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ColorRGB{ Red, Green, Blue }
@@ -188,4 +520,62 @@ pub enum ColorHSV{ Hue, Saturation, Value }
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ColorHSL{ Hue, Saturation, Lightness }
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
-pub enum ColorHSI{ Hue, Saturation, Intensity }
\ No newline at end of file
+pub enum ColorHSI{ Hue, Saturation, Intensity }
+///Emitters, that are not part of a color model.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ColorSingle{ White, Amber, UltraViolet }
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rgb(ColorRGB::Red) => write!(f, "Red"),
+            Self::Rgb(ColorRGB::Green) => write!(f, "Green"),
+            Self::Rgb(ColorRGB::Blue) => write!(f, "Blue"),
+            Self::Hsv(ColorHSV::Hue) => write!(f, "Hue (HSV)"),
+            Self::Hsv(ColorHSV::Saturation) => write!(f, "Saturation (HSV)"),
+            Self::Hsv(ColorHSV::Value) => write!(f, "Value (HSV)"),
+            Self::Hsl(ColorHSL::Hue) => write!(f, "Hue (HSL)"),
+            Self::Hsl(ColorHSL::Saturation) => write!(f, "Saturation (HSL)"),
+            Self::Hsl(ColorHSL::Lightness) => write!(f, "Lightness (HSL)"),
+            Self::Hsi(ColorHSI::Hue) => write!(f, "Hue (HSI)"),
+            Self::Hsi(ColorHSI::Saturation) => write!(f, "Saturation (HSI)"),
+            Self::Hsi(ColorHSI::Intensity) => write!(f, "Intensity (HSI)"),
+            Self::Single(ColorSingle::White) => write!(f, "White"),
+            Self::Single(ColorSingle::Amber) => write!(f, "Amber"),
+            Self::Single(ColorSingle::UltraViolet) => write!(f, "UV"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_join_round_trip() {
+        for value in [0, 1, 255, 256, 0x1234, 0xff00, u16::MAX] {
+            let (coarse, fine) = split_u16(value);
+            assert_eq!(join_u16(coarse, fine), value, "joining the split bytes must result in the original value");
+        }
+        assert_eq!(split_u16(0x1234), (0x12, 0x34), "the coarse byte must be the high byte");
+    }
+
+    #[test]
+    fn u16_values() {
+        let coarse = Channel::new_coarse(SimpleAction::IntensityMasterDimmer, 3);
+        assert_eq!(coarse.u16_values(1, 0x1234), vec![(1, 0x12), (3, 0x34)], "both bytes must be written to their channels");
+        let single = Channel::new_simple(SimpleAction::IntensityMasterDimmer);
+        assert_eq!(single.u16_values(1, 0x1234), vec![(1, 0x12)], "without a fine channel only the coarse byte must be written");
+    }
+
+    #[test]
+    fn selection_lists_each_action_once() {
+        let selection = Action::Selection(Arc::new([
+            Range::new(0, 9, SimpleAction::Strobo),
+            Range::new(10, 19, SimpleAction::NoOp),
+            Range::new(20, 29, SimpleAction::Speed),
+            Range::new(30, 39, SimpleAction::Strobo),
+        ]));
+        assert_eq!(selection.to_string(), "Speed / Strobe", "duplicate actions must only be shown once, even if they are not adjacent");
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use serde_derive::{Deserialize, Serialize};
+
+///Maps the value of an intensity channel (see [`SimpleAction::is_intensity`](crate::artnet::fixture::channel::SimpleAction::is_intensity))
+///to the value, that actually gets sent.
+///This corrects e.g. LEDs, which look too bright at the low end, when they are dimmed linearly.
+///
+///All curves map 0 to 0 and the maximum to the maximum (except for [`Self::Custom`] ones).
+#[derive(Debug, Default, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ResponseCurve{
+    #[default]
+    Linear,
+    ///Darker at the low end: `x²`
+    Square,
+    ///Smooth at both ends: `3x² - 2x³`
+    SCurve,
+    ///Brighter at the low end: `1 - (1 - x)²`
+    InverseSquare,
+    ///A lookup table of evenly spaced points, between which is interpolated linearly.
+    ///The first point is the output for 0, the last point the output for the maximum.
+    Custom(Arc<[u8]>),
+}
+
+///`u16::MAX`
+const MAX: u64 = 65_535;
+
+impl ResponseCurve {
+    ///All curves, that don't need any further data. Useful for presenting a choice in the ui.
+    pub const PRESETS: [Self; 4] = [Self::Linear, Self::Square, Self::SCurve, Self::InverseSquare];
+
+    #[inline]
+    pub const fn is_linear(&self) -> bool {
+        matches!(self, Self::Linear)
+    }
+
+    ///Applies this curve to the value of a 16-bit (coarse and fine) channel.
+    pub fn apply_u16(&self, value: u16) -> u16 {
+        let x = u64::from(value);
+        let out = match self {
+            Self::Linear => x,
+            Self::Square => x * x / MAX,
+            Self::SCurve => x * x * (3 * MAX - 2 * x) / (MAX * MAX),
+            Self::InverseSquare => MAX - (MAX - x) * (MAX - x) / MAX,
+            Self::Custom(points) => Self::interpolate(points, x),
+        };
+        u16::try_from(out).unwrap_or(u16::MAX)
+    }
+
+    ///Applies this curve to the value of an 8-bit channel.
+    pub fn apply(&self, value: u8) -> u8 {
+        //257 maps 255 exactly to u16::MAX
+        u8::try_from(u32::from(self.apply_u16(u16::from(value) * 257)) / 257).unwrap_or(u8::MAX)
+    }
+
+    fn interpolate(points: &[u8], x: u64) -> u64 {
+        let point = |index: u64| usize::try_from(index).ok()
+            .and_then(|index|points.get(index))
+            .map_or(0, |point|u64::from(*point) * 257);
+        let Some(segments) = u64::try_from(points.len()).ok().and_then(|len|len.checked_sub(1)).filter(|segments|*segments > 0) else {
+            //a single point is a constant, no points mean no change
+            return points.first().map_or(x, |point|u64::from(*point) * 257);
+        };
+        let position = x * segments;
+        let index = (position / MAX).min(segments - 1);
+        let fraction = position - index * MAX;
+        let (from, to) = (point(index), point(index + 1));
+        if to >= from {
+            from + (to - from) * fraction / MAX
+        } else {
+            from - (from - to) * fraction / MAX
+        }
+    }
+}
+
+impl Display for ResponseCurve {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linear => write!(f, "Linear"),
+            Self::Square => write!(f, "Square"),
+            Self::SCurve => write!(f, "S-Curve"),
+            Self::InverseSquare => write!(f, "Inverse Square"),
+            Self::Custom(points) => write!(f, "Custom ({} Points)", points.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_keep_end_points() {
+        for curve in ResponseCurve::PRESETS {
+            assert_eq!(curve.apply_u16(0), 0, "{curve} must map 0 to 0");
+            assert_eq!(curve.apply_u16(u16::MAX), u16::MAX, "{curve} must map the maximum to the maximum");
+            assert_eq!(curve.apply(0), 0, "{curve} must map 0 to 0 for 8-bit values");
+            assert_eq!(curve.apply(u8::MAX), u8::MAX, "{curve} must map the maximum to the maximum for 8-bit values");
+        }
+    }
+
+    #[test]
+    fn interpolate_without_points_is_identity() {
+        for x in [0, 1, 12_345, MAX] {
+            assert_eq!(ResponseCurve::interpolate(&[], x), x, "no points must not change {x}");
+        }
+    }
+
+    #[test]
+    fn interpolate_single_point_is_constant() {
+        for x in [0, 12_345, MAX] {
+            assert_eq!(ResponseCurve::interpolate(&[128], x), 128 * 257, "a single point must be returned for {x}");
+        }
+    }
+
+    #[test]
+    fn interpolate_multiple_points() {
+        for x in [0, 1, 12_345, MAX] {
+            assert_eq!(ResponseCurve::interpolate(&[0, 255], x), x, "two points from 0 to the maximum must be linear at {x}");
+        }
+        let peak = [0, 255, 0];
+        assert_eq!(ResponseCurve::interpolate(&peak, 0), 0, "the first point must be the output for 0");
+        assert_eq!(ResponseCurve::interpolate(&peak, MAX), 0, "the last point must be the output for the maximum");
+        assert_eq!(ResponseCurve::interpolate(&peak, MAX / 4), 32_766, "rising segments must be interpolated");
+        assert_eq!(ResponseCurve::interpolate(&peak, MAX - MAX / 4), 32_766, "falling segments must be interpolated");
+        let steps = [0, 0, 255, 255];
+        assert_eq!(ResponseCurve::interpolate(&steps, MAX / 3), 0, "flat segments must stay flat");
+        assert_eq!(ResponseCurve::interpolate(&steps, MAX), MAX, "the last point must be reached");
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use crate::artnet::fixture::channel::{Action, Range, SimpleAction};
+use crate::artnet::fixture::{Fixture, FixtureMode};
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Severity{
+    ///The fixture works, but something looks off.
+    Warning,
+    ///The fixture will not behave as intended.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "Warning"),
+            Self::Error => write!(f, "Error"),
+        }
+    }
+}
+
+///Where in a fixture a [`Lint`] was found.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ChannelLocation{
+    mode: Arc<str>,
+    ///0-based offset of the channel in the mode
+    channel: usize,
+}
+
+impl Display for ChannelLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Mode \"{}\", Channel {}", self.mode, self.channel + 1)
+    }
+}
+
+///A problem in a fixture definition.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, thiserror::Error)]
+pub enum Lint{
+    #[error("The {0} of the fixture is empty.")]
+    EmptyName(&'static str),
+    #[error("The name of mode {} is empty.", .0 + 1)]
+    EmptyModeName(usize),
+    #[error("Mode \"{0}\" has no channels.")]
+    EmptyMode(Arc<str>),
+    #[error("{0}: The slot of the range {1}-{2} has an empty name.")]
+    EmptySlotName(ChannelLocation, u8, u8),
+    #[error("{0}: The ranges {1}-{2} and {3}-{4} overlap.")]
+    OverlappingRanges(ChannelLocation, u8, u8, u8, u8),
+    #[error("{0}: The values {1}-{2} are not covered by any range.")]
+    Gap(ChannelLocation, u8, u8),
+    #[error("{0}: The range {1}-{2} is inverted, but different values in it have the same effect. This is probably a mistake.")]
+    InvertedRange(ChannelLocation, u8, u8),
+    #[error("{0}: The default value of the variable is not one of the selectable values.")]
+    DefaultNotSelectable(ChannelLocation),
+    #[error("{0}: The coarse and fine channel of this 16-bit attribute do not refer to each other.")]
+    InvalidFinePair(ChannelLocation),
+    #[error("{0}: This attribute cannot be split into a coarse and a fine channel.")]
+    FineNotSupported(ChannelLocation),
+    #[error("There are multiple fixtures at the path {0}.")]
+    DuplicatePath(String),
+}
+
+impl Lint {
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::Gap(..) |
+            Self::InvertedRange(..) |
+            Self::EmptyMode(_) |
+            Self::EmptySlotName(..)
+                => Severity::Warning,
+            Self::EmptyName(_) |
+            Self::EmptyModeName(_) |
+            Self::OverlappingRanges(..) |
+            Self::DefaultNotSelectable(_) |
+            Self::InvalidFinePair(_) |
+            Self::FineNotSupported(_) |
+            Self::DuplicatePath(_)
+                => Severity::Error,
+        }
+    }
+}
+
+///Checks a fixture definition for common mistakes.
+pub fn validate_fixture(fixture: &Fixture) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    for (name, value) in [
+        ("manufacturer", fixture.get_manufacturer()),
+        ("model", fixture.get_model()),
+        ("type", fixture.get_type()),
+    ] {
+        if value.trim().is_empty() {
+            lints.push(Lint::EmptyName(name));
+        }
+    }
+    for (i, mode) in fixture.get_modes().iter().enumerate() {
+        if mode.get_name().trim().is_empty() {
+            lints.push(Lint::EmptyModeName(i));
+        }
+        validate_mode(mode, &mut lints);
+    }
+    lints
+}
+
+fn validate_mode(mode: &FixtureMode, lints: &mut Vec<Lint>) {
+    let channels = mode.get_channels();
+    if channels.is_empty() {
+        lints.push(Lint::EmptyMode(mode.get_name().clone()));
+    }
+    for (offset, channel) in channels.iter().enumerate() {
+        let location = ChannelLocation{
+            mode: mode.get_name().clone(),
+            channel: offset,
+        };
+        let fine_pair_valid = match (channel.get_fine(), channel.get_action()) {
+            (Some(fine), _) =>
+                channels.get(usize::from(fine)).and_then(|fine|fine.get_coarse()).is_some_and(|coarse|usize::from(coarse) == offset),
+            (None, Action::Fine(coarse)) =>
+                channels.get(usize::from(*coarse)).and_then(|coarse|coarse.get_fine()).is_some_and(|fine|usize::from(fine) == offset),
+            (None, _) => true,
+        };
+        if !fine_pair_valid {
+            lints.push(Lint::InvalidFinePair(location.clone()));
+        }
+        let fine_supported = match channel.get_action() {
+            Action::SimpleAction(action) => action.supports_fine(),
+            Action::Selection(_) |
+            Action::Fine(_) => false,
+        };
+        if channel.get_fine().is_some() && !fine_supported {
+            lints.push(Lint::FineNotSupported(location.clone()));
+        }
+        match channel.get_action() {
+            Action::SimpleAction(action) => validate_action(&location, action, lints),
+            Action::Selection(ranges) => validate_selection(&location, ranges, lints),
+            Action::Fine(_) => {},
+        }
+    }
+}
+
+fn validate_action(location: &ChannelLocation, action: &SimpleAction, lints: &mut Vec<Lint>) {
+    if let SimpleAction::VariableChannelAction(action) = action {
+        if !action.get_variable().default_is_selectable() {
+            lints.push(Lint::DefaultNotSelectable(location.clone()));
+        }
+    }
+}
+
+fn validate_selection(location: &ChannelLocation, ranges: &[Range], lints: &mut Vec<Lint>) {
+    let mut sorted = ranges.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|range|(range.get_start(), range.get_end()));
+
+    //first value, that is not covered by any previous range
+    let mut next_free = 0u16;
+    let mut prev: Option<&Range> = None;
+    for range in sorted {
+        validate_action(location, range.get_action(), lints);
+        if range.get_slot().is_some_and(|slot|slot.get_name().trim().is_empty()) {
+            lints.push(Lint::EmptySlotName(location.clone(), range.get_start(), range.get_end()));
+        }
+        if range.is_inverted() && !range.is_continuous() {
+            lints.push(Lint::InvertedRange(location.clone(), range.get_start(), range.get_end()));
+        }
+
+        let start = u16::from(range.get_start());
+        match prev {
+            Some(prev) if start < next_free => lints.push(Lint::OverlappingRanges(
+                location.clone(),
+                prev.get_start(),
+                prev.get_end(),
+                range.get_start(),
+                range.get_end(),
+            )),
+            Some(_) | None => if start > next_free {
+                lints.push(Lint::Gap(location.clone(), u8::try_from(next_free).unwrap_or(u8::MAX), range.get_start() - 1));
+            },
+        }
+        next_free = u16::max(next_free, u16::from(range.get_end()) + 1);
+        prev = Some(range);
+    }
+    if next_free <= u16::from(u8::MAX) {
+        lints.push(Lint::Gap(location.clone(), u8::try_from(next_free).unwrap_or(u8::MAX), u8::MAX));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artnet::fixture::channel::{Channel, Slot};
+
+    fn fixture(name: &str, channels: Arc<[Channel]>) -> Fixture {
+        Fixture::new(Arc::from("Test"), Arc::from(name), Arc::from("Test"), channels)
+    }
+
+    fn selection_lints(ranges: &[Range]) -> Vec<Lint> {
+        validate_fixture(&fixture("Selection", Arc::new([Channel::new(Action::Selection(Arc::from(ranges)))])))
+    }
+
+    #[test]
+    fn complete_selection_has_no_lints() {
+        let lints = selection_lints(&[
+            Range::new(0, 9, SimpleAction::NoOp),
+            Range::new(10, 255, SimpleAction::Strobo),
+        ]);
+        assert!(lints.is_empty(), "a selection covering all values must not produce lints, got {lints:?}");
+    }
+
+    #[test]
+    fn overlap() {
+        let lints = selection_lints(&[
+            Range::new(0, 127, SimpleAction::NoOp),
+            Range::new(100, 255, SimpleAction::Strobo),
+        ]);
+        assert!(
+            matches!(lints.as_slice(), [Lint::OverlappingRanges(_, 0, 127, 100, 255)]),
+            "overlapping ranges must be reported, got {lints:?}"
+        );
+        assert!(lints.iter().all(|lint|lint.severity() == Severity::Error), "overlaps must be errors");
+    }
+
+    #[test]
+    fn gaps() {
+        let lints = selection_lints(&[
+            Range::new(10, 99, SimpleAction::NoOp),
+            Range::new(200, 249, SimpleAction::Strobo),
+        ]);
+        assert_eq!(lints.len(), 3, "the start, middle and end gap must be reported, got {lints:?}");
+        assert!(matches!(lints.first(), Some(Lint::Gap(_, 0, 9))), "the gap at the start must be reported, got {lints:?}");
+        assert!(matches!(lints.get(1), Some(Lint::Gap(_, 100, 199))), "the gap in the middle must be reported, got {lints:?}");
+        assert!(matches!(lints.get(2), Some(Lint::Gap(_, 250, 255))), "the gap at the end must be reported, got {lints:?}");
+        assert!(lints.iter().all(|lint|lint.severity() == Severity::Warning), "gaps must be warnings");
+    }
+
+    #[test]
+    fn inverted_range() {
+        let lints = selection_lints(&[
+            Range::new(127, 0, SimpleAction::NoOp),
+            Range::new(255, 128, SimpleAction::Strobo),
+        ]);
+        assert!(
+            matches!(lints.as_slice(), [Lint::InvertedRange(_, 0, 127)]),
+            "only the inverted range without a continuous effect must be reported, got {lints:?}"
+        );
+    }
+
+    #[test]
+    fn fine_channels() {
+        let lints = validate_fixture(&fixture("Fine", Arc::new([
+            Channel::new_coarse(SimpleAction::IntensityMasterDimmer, 1),
+            Channel::new_fine(0),
+        ])));
+        assert!(lints.is_empty(), "a 16-bit dimmer must not produce lints, got {lints:?}");
+
+        let lints = validate_fixture(&fixture("Fine", Arc::new([
+            Channel::new_coarse(SimpleAction::Strobo, 1),
+            Channel::new_fine(0),
+        ])));
+        assert!(matches!(lints.as_slice(), [Lint::FineNotSupported(_)]), "a fine channel on strobe must be reported, got {lints:?}");
+
+        let lints = validate_fixture(&fixture("Fine", Arc::new([
+            Channel::new_coarse(SimpleAction::IntensityMasterDimmer, 1),
+            Channel::new_simple(SimpleAction::NoOp),
+        ])));
+        assert!(matches!(lints.as_slice(), [Lint::InvalidFinePair(_)]), "a fine channel, that doesn't refer back, must be reported, got {lints:?}");
+    }
+
+    #[test]
+    fn empty_names() {
+        let lints = selection_lints(&[
+            Range::new(0, 255, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from(" "))),
+        ]);
+        assert!(matches!(lints.as_slice(), [Lint::EmptySlotName(_, 0, 255)]), "empty slot names must be reported, got {lints:?}");
+
+        let lints = validate_fixture(&fixture(" ", Arc::new([Channel::new_simple(SimpleAction::NoOp)])));
+        assert!(matches!(lints.as_slice(), [Lint::EmptyName("model")]), "an empty model must be reported, got {lints:?}");
+        assert!(lints.iter().all(|lint|lint.severity() == Severity::Error), "empty names must be errors");
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use serde_derive::{Deserialize, Serialize};
 use crate::degree::scale_deg;
@@ -20,19 +21,43 @@ impl<T> Variable<T> {
     }
 }
 
+impl<T: PartialEq> Variable<T> {
+    ///False, if this is a `Selection`, whose default is not one of the selectable values.
+    pub fn default_is_selectable(&self) -> bool {
+        match self {
+            Self::Set(_) => true,
+            Self::Selection(values, default) => values.contains(default),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 #[allow(clippy::enum_variant_names)]
 pub enum VariableChannelAction{
     ///data is total range of this channel in micro-arc-seconds
     PositionPan(Variable<u64>),
     ///data is total range of this channel in micro-arc-seconds
+    ///
+    ///Legacy: this is an independent 8-bit channel and is only kept, so that older projects still load.
+    ///Use a [`Channel::new_coarse`](crate::artnet::fixture::channel::Channel::new_coarse) `PositionPan` channel instead.
     PositionPanFine(Variable<u64>),
     ///data is total range of this channel in micro-arc-seconds
     PositionTilt(Variable<u64>),
     ///data is total range of this channel in micro-arc-seconds
+    ///
+    ///Legacy: this is an independent 8-bit channel and is only kept, so that older projects still load.
+    ///Use a [`Channel::new_coarse`](crate::artnet::fixture::channel::Channel::new_coarse) `PositionTilt` channel instead.
     PositionTiltFine(Variable<u64>),
 }
 impl VariableChannelAction{
+    pub const fn get_variable(&self) -> &Variable<u64> {
+        match self {
+            Self::PositionPan(v) |
+            Self::PositionPanFine(v) |
+            Self::PositionTilt(v) |
+            Self::PositionTiltFine(v) => v,
+        }
+    }
     pub fn resolve(&self, variable_selection: VariableSelection) -> ResolvedVariableChannelAction{
         match self {
             Self::PositionPan(v) => ResolvedVariableChannelAction::PositionPan(*v.select(variable_selection.pan)),
@@ -43,6 +68,17 @@ impl VariableChannelAction{
     }
 }
 
+impl Display for VariableChannelAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PositionPan(_) => write!(f, "Pan"),
+            Self::PositionPanFine(_) => write!(f, "Pan Fine"),
+            Self::PositionTilt(_) => write!(f, "Tilt"),
+            Self::PositionTiltFine(_) => write!(f, "Tilt Fine"),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
 #[allow(clippy::enum_variant_names)]
 pub enum ResolvedVariableChannelAction{
@@ -61,18 +97,52 @@ pub enum ResolvedVariableChannelAction{
 }
 
 impl ResolvedVariableChannelAction {
-    pub const fn scale_to_range(&self, input: u64) -> u64 {
+    ///The total range of this channel in micro-arc-seconds.
+    #[inline]
+    pub const fn range(&self) -> u64 {
         match self {
             Self::PositionPan(range) |
             Self::PositionPanFine(range) |
             Self::PositionTilt(range) |
             Self::PositionTiltFine(range)
-            =>  scale_deg(*range, input, 256),
+            => *range,
+        }
+    }
+    ///Scales `input` (in micro-arc-seconds) to an 8-bit dmx value.
+    ///Values beyond the range of this channel get clamped.
+    pub const fn scale_to_range(&self, input: u64) -> u64 {
+        Self::scale_clamped(input, self.range(), 1<<8)
+    }
+    ///Scales `input` (in micro-arc-seconds) to the 16-bit value of a coarse/fine channel pair.
+    ///Values beyond the range of this channel get clamped.
+    ///
+    ///The coarse byte of the result is always equal to [`Self::scale_to_range`].
+    #[allow(clippy::cast_possible_truncation)] //scale_clamped never returns more than u16::MAX here
+    pub const fn scale_to_range_u16(&self, input: u64) -> u16 {
+        Self::scale_clamped(input, self.range(), 1<<16) as u16
+    }
+
+    ///Converts the 16-bit value of a coarse/fine channel pair back into micro-arc-seconds.
+    ///This is the inverse of [`Self::scale_to_range_u16`] (except for rounding).
+    #[allow(clippy::cast_lossless)] //u64::from is not const
+    pub const fn scale_from_range_u16(&self, value: u16) -> u64 {
+        scale_deg(value as u64, 1<<16, self.range())
+    }
+
+    const fn scale_clamped(input: u64, range: u64, output_range: u64) -> u64 {
+        if range == 0 {
+            return 0;
+        }
+        let out = scale_deg(input, range, output_range);
+        if out >= output_range {
+            output_range - 1
+        } else {
+            out
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Default, Deserialize, Serialize, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct VariableSelection{
     pan: Option<usize>,
     panfine: Option<usize>,
@@ -89,4 +159,71 @@ impl VariableSelection{
             tiltfine,
         }
     }
-}
\ No newline at end of file
+
+    ///Returns the selected option for `action`. `None` means, that the default of the [`Variable`] is used.
+    pub const fn get(&self, action: &VariableChannelAction) -> Option<usize> {
+        match action {
+            VariableChannelAction::PositionPan(_) => self.pan,
+            VariableChannelAction::PositionPanFine(_) => self.panfine,
+            VariableChannelAction::PositionTilt(_) => self.tilt,
+            VariableChannelAction::PositionTiltFine(_) => self.tiltfine,
+        }
+    }
+
+    ///Selects the option with the index `selection` for `action`. `None` selects the default of the [`Variable`].
+    pub fn set(&mut self, action: &VariableChannelAction, selection: Option<usize>) {
+        match action {
+            VariableChannelAction::PositionPan(_) => self.pan = selection,
+            VariableChannelAction::PositionPanFine(_) => self.panfine = selection,
+            VariableChannelAction::PositionTilt(_) => self.tilt = selection,
+            VariableChannelAction::PositionTiltFine(_) => self.tiltfine = selection,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::degree::deg_to_microarcseconds;
+
+    const RANGES: [u64; 3] = [deg_to_microarcseconds(180), deg_to_microarcseconds(270), deg_to_microarcseconds(540)];
+
+    fn inputs(range: u64) -> impl Iterator<Item = u64> {
+        (0..=1000).map(move |step|range / 1000 * step).chain([1, range - 1, range + 1])
+    }
+
+    #[test]
+    fn known_positions() {
+        //(degrees, coarse, fine, 8-bit value) for a range of 540°, computed by hand:
+        //16-bit: degrees * 65536 / 540, 8-bit: degrees * 256 / 540, both clamped to their maximum
+        let expected = [
+            (0, 0, 0, 0),
+            (90, 0x2A, 0xAA, 42),
+            (180, 0x55, 0x55, 85),
+            (270, 0x80, 0x00, 128),
+            (540, 0xFF, 0xFF, 255),
+            (600, 0xFF, 0xFF, 255),
+        ];
+        let action = ResolvedVariableChannelAction::PositionPan(deg_to_microarcseconds(540));
+        for (degrees, coarse, fine, value) in expected {
+            let input = deg_to_microarcseconds(degrees);
+            let [actual_coarse, actual_fine] = action.scale_to_range_u16(input).to_be_bytes();
+            assert_eq!(actual_coarse, coarse, "wrong coarse byte for {degrees}°");
+            assert_eq!(actual_fine, fine, "wrong fine byte for {degrees}°");
+            assert_eq!(action.scale_to_range(input), value, "wrong 8-bit value for {degrees}°");
+        }
+        assert_eq!(action.scale_from_range_u16(0x8000), deg_to_microarcseconds(270), "the middle of the 16-bit range must be 270°");
+    }
+
+    #[test]
+    fn scale_from_range_u16_inverts_within_one_step() {
+        for range in RANGES {
+            let action = ResolvedVariableChannelAction::PositionTilt(range);
+            let step = range.div_ceil(1<<16);
+            for input in inputs(range).filter(|input|*input <= range) {
+                let back = action.scale_from_range_u16(action.scale_to_range_u16(input));
+                assert!(input.abs_diff(back) <= step, "{input} must be restored within one step, but was {back}");
+            }
+        }
+    }
+}
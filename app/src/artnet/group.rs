@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use serde_derive::{Deserialize, Serialize};
+use crate::artnet::fixture::{Device, DeviceId};
+use crate::artnet::universe::{UniverseDevices, Universes};
+
+///A named collection of devices across all universes (e.g. "stage left movers" or "all pars").
+#[derive(Debug, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DeviceGroup{
+    pub name: Arc<str>,
+    members: Vec<DeviceId>,
+    ///Submaster, that scales the intensity channels of all members.
+    #[serde(default = "full_master")]
+    pub master: u8,
+}
+
+const fn full_master() -> u8 {
+    u8::MAX
+}
+
+impl DeviceGroup {
+    pub const fn new(name: Arc<str>) -> Self {
+        Self{
+            name,
+            members: Vec::new(),
+            master: u8::MAX,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    #[inline]
+    pub fn members(&self) -> &[DeviceId] {
+        self.members.as_slice()
+    }
+
+    pub fn contains(&self, device: DeviceId) -> bool {
+        self.members.contains(&device)
+    }
+
+    ///Adds `device` to the group, if it isn't already part of it.
+    pub fn add(&mut self, device: DeviceId) {
+        if !self.contains(device) {
+            self.members.push(device);
+        }
+    }
+
+    pub fn remove(&mut self, device: DeviceId) {
+        self.members.retain(|other|*other != device);
+    }
+
+    ///Returns the universe index and the device of all members of this group, which still exist in `devices`.
+    pub fn resolve<'a>(&'a self, devices: &'a Universes<UniverseDevices>) -> impl Iterator<Item = (usize, &'a Device)> + 'a {
+        self.members.iter()
+            .filter_map(|id|devices.find(*id).map(|(universe, _, device)|(universe, device)))
+    }
+}
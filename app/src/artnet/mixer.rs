@@ -0,0 +1,76 @@
+//!Computes the dmx values, that actually get sent for a universe.
+use crate::artnet::fixture::channel::{join_u16, split_u16, Action};
+use crate::artnet::fixture::{Device, DeviceId};
+use crate::artnet::universe::{UniverseChannels, UniverseDevices};
+
+///Scales `value` by `master`, where `u8::MAX` is full.
+#[inline]
+pub fn scale(value: u8, master: u8) -> u8 {
+    u8::try_from(u16::from(value) * u16::from(master) / u16::from(u8::MAX)).unwrap_or(u8::MAX)
+}
+
+///Like [`scale`], but for the value of a 16-bit (coarse and fine) channel.
+#[inline]
+pub fn scale_u16(value: u16, master: u8) -> u16 {
+    u16::try_from(u32::from(value) * u32::from(master) / u32::from(u8::MAX)).unwrap_or(u16::MAX)
+}
+
+///Computes the output of one universe from the values set in the ui (`channels`).
+///Channels, which are not set, are 0.
+///
+///The intensity channels of `devices` get scaled by `multiplier` (the global and universe multiplier)
+///and the submaster, that `master` returns for the device. Then the response curve of the device is applied.
+///All other channels of devices (e.g. position or GOBO) are never scaled.
+///Channels, that don't belong to any device, are only scaled by `multiplier`, if `scale_unpatched` is set.
+pub fn mix_universe(devices: Option<&UniverseDevices>, channels: Option<&UniverseChannels<Option<u8>>>, multiplier: u8, scale_unpatched: bool, master: impl Fn(DeviceId) -> u8) -> UniverseChannels<u8> {
+    let mut output = UniverseChannels::default();
+    for (out, value) in output.iter_mut().zip(channels.into_iter().flat_map(UniverseChannels::iter)) {
+        *out = value.unwrap_or_default();
+    }
+    if scale_unpatched && multiplier != u8::MAX {
+        let mut patched = UniverseChannels::<bool>::default();
+        for device in devices.into_iter().flatten() {
+            let start = usize::from(device.start_channel());
+            for channel in (start..usize::from(device.end_channel())).filter_map(|channel|ux2::u9::try_from(channel).ok()) {
+                *patched.get_mut(channel) = true;
+            }
+        }
+        for (out, patched) in output.iter_mut().zip(patched.iter()) {
+            if !patched {
+                *out = scale(*out, multiplier);
+            }
+        }
+    }
+    for device in devices.into_iter().flatten() {
+        apply_intensity(device, scale(multiplier, master(device.get_id())), &mut output);
+    }
+    output
+}
+
+///Applies `master` and the response curve of `device` to its intensity channels in `output`.
+///16-bit channels get scaled as their combined coarse and fine value.
+fn apply_intensity(device: &Device, master: u8, output: &mut UniverseChannels<u8>) {
+    let curve = device.get_response_curve();
+    if curve.is_linear() && master == u8::MAX {
+        return;
+    }
+    for (offset, channel) in device.get_channels().iter().enumerate() {
+        let Action::SimpleAction(action) = channel.get_action() else { continue };
+        if !action.is_intensity() {
+            continue;
+        }
+        let Some(coarse) = device.channel_id(offset) else { continue };
+        match channel.get_fine().and_then(|fine|device.channel_id(usize::from(fine))) {
+            Some(fine) => {
+                let value = scale_u16(join_u16(*output.get(coarse), *output.get(fine)), master);
+                let (coarse_value, fine_value) = split_u16(curve.apply_u16(value));
+                *output.get_mut(coarse) = coarse_value;
+                *output.get_mut(fine) = fine_value;
+            },
+            None => {
+                let value = output.get_mut(coarse);
+                *value = curve.apply(scale(*value, master));
+            },
+        }
+    }
+}
@@ -1,8 +1,9 @@
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 use std::slice::{Iter, IterMut};
 use serde_derive::{Deserialize, Serialize};
-use crate::artnet::fixture::{Device};
+use crate::artnet::fixture::{Device, DeviceId};
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct UniverseChannelData<T>([[T;32];16]);
@@ -85,11 +86,101 @@ impl<T> Universes<T> {
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         self.data.iter_mut()
     }
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.data.get(index)
+    }
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
         self.data.get_mut(index)
     }
 }
 
+impl Universes<UniverseDevices> {
+    ///Finds the device with `id`.
+    ///Returns the index of it's universe, it's index in that universe and the device itself.
+    pub fn find(&self, id: DeviceId) -> Option<(usize, usize, &Device)> {
+        self.data.iter()
+            .enumerate()
+            .find_map(|(universe, devices)|{
+                devices.position(id).and_then(|index|devices.devices.get(index).map(|device|(universe, index, device)))
+            })
+    }
+
+    ///Gives every device without an id or with an id, that another device already has, a new unique id.
+    ///Afterwards no new device will get an id, which is already used.
+    ///
+    ///This should be called after loading a project.
+    ///The assigned ids only depend on the devices, so identical copies of the devices get identical ids.
+    pub fn assign_device_ids(&mut self) {
+        let mut max = self.data.iter()
+            .flat_map(UniverseDevices::iter)
+            .map(Device::get_id)
+            .max()
+            .unwrap_or(DeviceId::UNASSIGNED);
+        let mut seen = HashSet::new();
+        for devices in &mut self.data {
+            for device in &mut devices.devices {
+                if !device.get_id().is_assigned() || !seen.insert(device.get_id()) {
+                    max = max.successor();
+                    device.set_id(max);
+                    seen.insert(max);
+                }
+            }
+        }
+        max.reserve();
+    }
+
+    ///Replaces the device at `index` in the universe `from` with `device`, which gets placed in the universe `to`.
+    ///
+    ///`device` gets validated the same way as in [`UniverseDevices::try_insert`].
+    ///This is atomic: If `device` cannot be placed, the old device stays where it was.
+    pub fn try_move(&mut self, from: ux2::u15, index: usize, to: ux2::u15, device: Device) -> Result<(), InsertError> {
+        let from_index = <ux2::u15 as Into<usize>>::into(from);
+        if self.data.get(from_index).map_or(true, |universe|index >= universe.len()) {
+            return Err(InsertError::DeviceNotFound);
+        }
+        if from == to {
+            return self.create_or_get_universe(to).try_replace(index, device).map(|_|());
+        }
+        self.create_or_get_universe(to).try_insert(device)?;
+        if let Some(universe) = self.data.get_mut(from_index) {
+            universe.remove(index);
+        }
+        Ok(())
+    }
+
+    ///Finds free start channels for `quantity` devices with `channels` channels each.
+    ///
+    ///The search starts at `start` in `universe`. Between two consecutive devices `gap` channels are left unused.
+    ///If a universe fills up, the search continues at the beginning of the next universe.
+    ///Returns the universe and start channel of every device, or an error if not all devices fit.
+    pub fn find_free_ranges(&self, quantity: usize, channels: usize, universe: ux2::u15, start: ux2::u9, gap: usize) -> Result<Vec<(ux2::u15, ux2::u9)>, InsertError> {
+        let empty = UniverseDevices::default();
+        let mut placements = Vec::with_capacity(quantity);
+        let mut universe = universe;
+        let mut from = usize::from(start);
+        while placements.len() < quantity {
+            let devices = self.data.get(<ux2::u15 as Into<usize>>::into(universe)).unwrap_or(&empty);
+            let found = ux2::u9::try_from(from).ok()
+                .and_then(|from|devices.find_free_range(channels, from, None));
+            match found {
+                Some(start_channel) => {
+                    placements.push((universe, start_channel));
+                    from = usize::from(start_channel) + channels + gap;
+                },
+                None => {
+                    //not even an empty universe can hold a device this big
+                    if from == 0 && devices.is_empty() {
+                        return Err(InsertError::NoSpaceLeft);
+                    }
+                    universe = universe.checked_add(ux2::u15::new(1)).ok_or(InsertError::NoSpaceLeft)?;
+                    from = 0;
+                },
+            }
+        }
+        Ok(placements)
+    }
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq)]
 pub struct UniverseDevices {
     devices: Vec<Device>,
@@ -112,7 +203,11 @@ pub enum InsertError {
     #[error("The ending Channel-Id is too big. {0} is bigger than 512")]
     EndIdTooBig(u64),
     #[error("At least one Channel is already assigned in the requested channel-range.")]
-    ChannelAlreadyAssigned
+    ChannelAlreadyAssigned,
+    #[error("The device does not exist.")]
+    DeviceNotFound,
+    #[error("There are not enough free channels left to place all devices.")]
+    NoSpaceLeft,
 }
 
 impl UniverseDevices {
@@ -132,12 +227,95 @@ impl UniverseDevices {
     pub fn iter(&self) -> Iter<Device> {
         self.devices.iter()
     }
+    ///Returns the index of the device with `id`.
+    pub fn position(&self, id: DeviceId) -> Option<usize> {
+        self.devices.iter().position(|device|device.get_id() == id)
+    }
     pub fn extend<I: IntoIterator<Item=Device>>(&mut self, iter: I) {
         self.devices.extend(iter)
     }
+    ///Returns the device occupying `channel` and the offset of `channel` relative to the start of that device.
+    pub fn device_at(&self, channel: ux2::u9) -> Option<(&Device, usize)> {
+        self.devices.iter()
+            .find(|device|device.start_channel() <= channel && channel < device.end_channel())
+            .map(|device|(
+                device,
+                <ux2::u9 as Into<usize>>::into(channel) - <ux2::u9 as Into<usize>>::into(device.start_channel())
+            ))
+    }
+    ///True, if no device other than the one at index `ignore` occupies any channel in `start..end`.
+    pub fn is_free(&self, start: ux2::u9, end: ux2::u9, ignore: Option<usize>) -> bool {
+        self.devices.iter()
+            .enumerate()
+            .filter(|(i, _)|Some(*i) != ignore)
+            .all(|(_, device)|device.end_channel() <= start || end <= device.start_channel())
+    }
+    ///Finds the first start channel at or after `from`, where a device with `channels` channels would fit.
+    ///The device at index `ignore` is treated as if it wasn't there.
+    pub fn find_free_range(&self, channels: usize, from: ux2::u9, ignore: Option<usize>) -> Option<ux2::u9> {
+        let mut occupied = self.devices.iter()
+            .enumerate()
+            .filter(|(i, _)|Some(*i) != ignore)
+            .map(|(_, device)|(usize::from(device.start_channel()), usize::from(device.end_channel())))
+            .collect::<Vec<_>>();
+        occupied.sort_unstable();
+        let mut start = usize::from(from);
+        for (device_start, device_end) in occupied {
+            if device_end <= start {
+                continue;
+            }
+            if start + channels <= device_start {
+                break;
+            }
+            start = device_end;
+        }
+        //the end channel (exclusive) needs to fit into an u9. See Device::new.
+        if start + channels > usize::from(ux2::u9::MAX) {
+            return None;
+        }
+        ux2::u9::try_from(start).ok()
+    }
+    ///Creates a copy of these devices, where the devices are moved towards channel 0 to remove all gaps between them.
+    ///The order of the devices stays the same and the devices in the copy are sorted by their start channel.
+    ///
+    ///If `align` is set, every device starts at a multiple of its channel count.
+    pub fn repacked(&self, align: bool) -> Result<Self, InsertError> {
+        let mut devices = self.devices.iter().collect::<Vec<_>>();
+        devices.sort_by_key(|device|device.start_channel());
+        let mut repacked = Self::default();
+        let mut next = 0usize;
+        for device in devices {
+            let channels = device.get_channels().len();
+            let start = if align && channels > 0 {
+                next.div_ceil(channels) * channels
+            } else {
+                next
+            };
+            let start_channel = ux2::u9::try_from(start).map_err(|_|InsertError::NoSpaceLeft)?;
+            let device = device.with_start_channel(start_channel).map_err(|_|InsertError::NoSpaceLeft)?;
+            next = usize::from(device.end_channel());
+            repacked.try_insert(device)?;
+        }
+        Ok(repacked)
+    }
     pub fn remove(&mut self, index: usize) -> Device {
         self.devices.remove(index)
     }
+    ///Replaces the device at `index` with `device`, if `device` doesn't collide with any other device.
+    ///On success the replaced device is returned. On failure the devices stay unchanged.
+    pub fn try_replace(&mut self, index: usize, device: Device) -> Result<Device, InsertError> {
+        if index >= self.devices.len() {
+            return Err(InsertError::DeviceNotFound);
+        }
+        let old = self.devices.remove(index);
+        match self.try_insert(device) {
+            Ok(()) => Ok(old),
+            Err(err) => {
+                self.devices.insert(index, old);
+                Err(err)
+            }
+        }
+    }
     pub fn try_insert(&mut self, device: Device) -> Result<(), InsertError> {
         let start_channel = device.start_channel();
         let end_channel = device.end_channel();
@@ -147,15 +325,16 @@ impl UniverseDevices {
             Ok(_) => Err(InsertError::ChannelAlreadyAssigned),
             Err(v) => {
                 //is there a previous element?
+                //note: the end channel of a device is exclusive
                 if v > 0{
                     let prev_dev = self.devices.index(v-1);
-                    if prev_dev.end_channel() >= start_channel {
+                    if prev_dev.end_channel() > start_channel {
                         return Err(InsertError::ChannelAlreadyAssigned);
                     }
                 }
-                //is there a next element?
-                if v+1 < self.devices.len() {
-                    let next_dev = self.devices.index(v+1);
+                //is there a next element? It is at the insertion point v.
+                if v < self.devices.len() {
+                    let next_dev = self.devices.index(v);
                     if end_channel > next_dev.start_channel() {
                         return Err(InsertError::ChannelAlreadyAssigned);
                     }
@@ -165,4 +344,66 @@ impl UniverseDevices {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::artnet::fixture::Fixture;
+    use crate::artnet::fixture::channel::{Channel, SimpleAction};
+
+    ///A device with 4 channels, starting at `start`.
+    fn device(start: u16) -> Option<Device> {
+        let fixture = Fixture::new(
+            Arc::from("Test"),
+            Arc::from("Four Channels"),
+            Arc::from("Test"),
+            Arc::new([
+                Channel::new_simple(SimpleAction::IntensityMasterDimmer),
+                Channel::new_simple(SimpleAction::NoOp),
+                Channel::new_simple(SimpleAction::NoOp),
+                Channel::new_simple(SimpleAction::NoOp),
+            ]),
+        );
+        Device::new_u16(Arc::from("Test"), start, fixture, 0).ok()
+    }
+
+    fn insert(devices: &mut UniverseDevices, start: u16) -> Option<Result<(), InsertError>> {
+        device(start).map(|device|devices.try_insert(device))
+    }
+
+    #[test]
+    fn adjacent_devices_fit() {
+        let mut devices = UniverseDevices::default();
+        assert_eq!(insert(&mut devices, 4), Some(Ok(())), "inserting into an empty universe must work");
+        assert_eq!(insert(&mut devices, 8), Some(Ok(())), "a device may start at the end channel of the previous one");
+        assert_eq!(insert(&mut devices, 0), Some(Ok(())), "a device may end at the start channel of the next one");
+        assert_eq!(devices.len(), 3, "all devices must be inserted");
+    }
+
+    #[test]
+    fn overlapping_devices_are_rejected() {
+        let mut devices = UniverseDevices::default();
+        assert_eq!(insert(&mut devices, 4), Some(Ok(())), "inserting into an empty universe must work");
+        assert_eq!(insert(&mut devices, 7), Some(Err(InsertError::ChannelAlreadyAssigned)), "the last channel of the previous device must be checked");
+        assert_eq!(insert(&mut devices, 1), Some(Err(InsertError::ChannelAlreadyAssigned)), "the first channel of the next device must be checked");
+        assert_eq!(insert(&mut devices, 4), Some(Err(InsertError::ChannelAlreadyAssigned)), "the same start channel must be rejected");
+        assert_eq!(devices.len(), 1, "rejected devices must not be inserted");
+    }
+
+    #[test]
+    fn inserting_between_two_devices() {
+        let mut devices = UniverseDevices::default();
+        assert_eq!(insert(&mut devices, 0), Some(Ok(())), "inserting into an empty universe must work");
+        assert_eq!(insert(&mut devices, 10), Some(Ok(())), "inserting after a device must work");
+        assert_eq!(insert(&mut devices, 8), Some(Err(InsertError::ChannelAlreadyAssigned)), "the device directly after the insertion point must be checked");
+        assert_eq!(insert(&mut devices, 2), Some(Err(InsertError::ChannelAlreadyAssigned)), "the device directly before the insertion point must be checked");
+        assert_eq!(insert(&mut devices, 5), Some(Ok(())), "a device fitting into the gap must be inserted");
+        assert_eq!(
+            devices.iter().map(|device|usize::from(device.start_channel())).collect::<Vec<_>>(),
+            vec![0, 5, 10],
+            "devices must stay sorted by their start channel"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//!Converts a picked RGB color into the values of whatever color channels a fixture has.
+//!
+//!All conversions only use integer math. Every component (including hue) uses the full dmx range of 0-255.
+use crate::artnet::fixture::channel::{Color, ColorHSI, ColorHSL, ColorHSV, ColorRGB, ColorSingle};
+
+///The color of an amber emitter in RGB.
+const AMBER: [u8; 3] = [255, 191, 0];
+
+#[inline]
+fn to_u8(value: u32) -> u8 {
+    u8::try_from(value).unwrap_or(u8::MAX)
+}
+
+///Returns the hue of `rgb` scaled to 0-255, where 0 (and 256) is red, ~85 is green and ~171 is blue.
+///Greys have a hue of 0.
+pub fn hue(rgb: [u8; 3]) -> u8 {
+    let [r, g, b] = rgb.map(i32::from);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    if delta == 0 {
+        return 0;
+    }
+    //hue in 1/256 of a sixth of the color wheel
+    let sixths = if max == r {
+        (256 * (g - b) / delta).rem_euclid(6 * 256)
+    } else if max == g {
+        2 * 256 + 256 * (b - r) / delta
+    } else {
+        4 * 256 + 256 * (r - g) / delta
+    };
+    to_u8(u32::try_from(sixths / 6).unwrap_or_default())
+}
+
+pub fn rgb_to_hsv(rgb: [u8; 3]) -> [u8; 3] {
+    let [r, g, b] = rgb.map(u32::from);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let saturation = if max == 0 { 0 } else { (max - min) * 255 / max };
+    [hue(rgb), to_u8(saturation), to_u8(max)]
+}
+
+pub fn rgb_to_hsl(rgb: [u8; 3]) -> [u8; 3] {
+    let [r, g, b] = rgb.map(u32::from);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let sum = max + min;
+    //255 * (1 - |2L - 1|), with L = sum / 510
+    let divisor = 255 - sum.abs_diff(255);
+    let saturation = if divisor == 0 { 0 } else { (max - min) * 255 / divisor };
+    [hue(rgb), to_u8(saturation), to_u8(sum / 2)]
+}
+
+pub fn rgb_to_hsi(rgb: [u8; 3]) -> [u8; 3] {
+    let [r, g, b] = rgb.map(u32::from);
+    let min = r.min(g).min(b);
+    let sum = r + g + b;
+    //1 - min / I, with I = sum / 3
+    let saturation = if sum == 0 { 0 } else { 255 - min * 3 * 255 / sum };
+    [hue(rgb), to_u8(saturation), to_u8(sum / 3)]
+}
+
+///Splits `rgb` into the part, that a white emitter can produce and the remaining RGB part.
+pub fn extract_white(rgb: [u8; 3]) -> (u8, [u8; 3]) {
+    let white = rgb.iter().copied().min().unwrap_or_default();
+    (white, rgb.map(|c|c - white))
+}
+
+///Splits `rgb` into the part, that an amber emitter can produce and the remaining RGB part.
+pub fn extract_amber(rgb: [u8; 3]) -> (u8, [u8; 3]) {
+    let [r, g, b] = rgb.map(u32::from);
+    let [amber_r, amber_g, _] = AMBER.map(u32::from);
+    let amber = (r * 255 / amber_r).min(g * 255 / amber_g);
+    let rest = [
+        r - amber * amber_r / 255,
+        g - amber * amber_g / 255,
+        b,
+    ];
+    (to_u8(amber), rest.map(to_u8))
+}
+
+///Reconstructs the RGB color from the values of the color channels of a fixture.
+///This is the exact inverse of [`color_channel_values`], but only works for fixtures with red, green and blue channels.
+///Returns `None` for all other fixtures or if one of those channels has no value.
+pub fn rgb_from_channel_values(channels: &[(Color, u8)]) -> Option<[u8; 3]> {
+    let value = |role: Color| channels.iter()
+        .find(|(other, _)|*other == role)
+        .map(|(_, value)|u32::from(*value));
+    let red = value(Color::Rgb(ColorRGB::Red))?;
+    let green = value(Color::Rgb(ColorRGB::Green))?;
+    let blue = value(Color::Rgb(ColorRGB::Blue))?;
+    let white = value(Color::Single(ColorSingle::White)).unwrap_or_default();
+    let amber = value(Color::Single(ColorSingle::Amber)).unwrap_or_default();
+    let [amber_r, amber_g, _] = AMBER.map(u32::from);
+    Some([
+        red + amber * amber_r / 255 + white,
+        green + amber * amber_g / 255 + white,
+        blue + white,
+    ].map(to_u8))
+}
+
+///False for color channels, whose value cannot be derived from an RGB color (e.g. UV).
+pub const fn is_derivable(role: Color) -> bool {
+    !matches!(role, Color::Single(ColorSingle::UltraViolet))
+}
+
+///Computes the dmx value of every color channel in `roles` for `rgb`.
+///The result has the same order as `roles`. Roles, that cannot be derived from `rgb` (e.g. UV) are `None`.
+///
+///If the fixture has white or amber emitters next to RGB emitters,
+///as much of the color as possible is produced by them (white first).
+pub fn color_channel_values(rgb: [u8; 3], roles: &[Color]) -> Vec<Option<u8>> {
+    let has_rgb = roles.iter().any(|role|matches!(role, Color::Rgb(_)));
+    let mut remaining = rgb;
+    let mut white = None;
+    let mut amber = None;
+    if has_rgb && roles.contains(&Color::Single(ColorSingle::White)) {
+        let (value, rest) = extract_white(remaining);
+        white = Some(value);
+        remaining = rest;
+    }
+    if has_rgb && roles.contains(&Color::Single(ColorSingle::Amber)) {
+        let (value, rest) = extract_amber(remaining);
+        amber = Some(value);
+        remaining = rest;
+    }
+    let [r, g, b] = remaining;
+    let [hsv_h, hsv_s, hsv_v] = rgb_to_hsv(rgb);
+    let [hsl_h, hsl_s, hsl_l] = rgb_to_hsl(rgb);
+    let [hsi_h, hsi_s, hsi_i] = rgb_to_hsi(rgb);
+    roles.iter()
+        .map(|role|match role {
+            Color::Rgb(ColorRGB::Red) => Some(r),
+            Color::Rgb(ColorRGB::Green) => Some(g),
+            Color::Rgb(ColorRGB::Blue) => Some(b),
+            Color::Hsv(ColorHSV::Hue) => Some(hsv_h),
+            Color::Hsv(ColorHSV::Saturation) => Some(hsv_s),
+            Color::Hsv(ColorHSV::Value) => Some(hsv_v),
+            Color::Hsl(ColorHSL::Hue) => Some(hsl_h),
+            Color::Hsl(ColorHSL::Saturation) => Some(hsl_s),
+            Color::Hsl(ColorHSL::Lightness) => Some(hsl_l),
+            Color::Hsi(ColorHSI::Hue) => Some(hsi_h),
+            Color::Hsi(ColorHSI::Saturation) => Some(hsi_s),
+            Color::Hsi(ColorHSI::Intensity) => Some(hsi_i),
+            //without rgb emitters the white/amber emitter is the only way to show the color
+            Color::Single(ColorSingle::White) => white.or(Some(extract_white(rgb).0)),
+            Color::Single(ColorSingle::Amber) => amber.or(Some(extract_amber(rgb).0)),
+            Color::Single(ColorSingle::UltraViolet) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::Rgb(ColorRGB::Red);
+    const GREEN: Color = Color::Rgb(ColorRGB::Green);
+    const BLUE: Color = Color::Rgb(ColorRGB::Blue);
+    const WHITE: Color = Color::Single(ColorSingle::White);
+    const AMBER_ROLE: Color = Color::Single(ColorSingle::Amber);
+    const UV: Color = Color::Single(ColorSingle::UltraViolet);
+
+    #[test]
+    fn hue_of_primaries_and_greys() {
+        assert_eq!(hue([255, 0, 0]), 0, "red");
+        assert_eq!(hue([0, 255, 0]), 85, "green");
+        assert_eq!(hue([0, 0, 255]), 170, "blue");
+        assert_eq!(hue([0, 0, 0]), 0, "black");
+        assert_eq!(hue([128, 128, 128]), 0, "grey");
+        assert_eq!(hue([255, 255, 255]), 0, "white");
+    }
+
+    #[test]
+    fn hue_rounding_limits() {
+        //just after red
+        assert_eq!(hue([255, 1, 0]), 0, "hue just after red");
+        //just before red wraps around to the end of the range instead of overflowing to 256
+        assert_eq!(hue([255, 0, 1]), 255, "hue just before red");
+    }
+
+    #[test]
+    fn hsv() {
+        assert_eq!(rgb_to_hsv([255, 0, 0]), [0, 255, 255], "red");
+        assert_eq!(rgb_to_hsv([0, 0, 255]), [170, 255, 255], "blue");
+        assert_eq!(rgb_to_hsv([0, 0, 0]), [0, 0, 0], "black");
+        assert_eq!(rgb_to_hsv([128, 128, 128]), [0, 0, 128], "grey");
+        assert_eq!(rgb_to_hsv([255, 255, 255]), [0, 0, 255], "white");
+        assert_eq!(rgb_to_hsv([255, 254, 254]), [0, 1, 255], "almost white keeps a little saturation");
+    }
+
+    #[test]
+    fn hsl() {
+        assert_eq!(rgb_to_hsl([255, 0, 0]), [0, 255, 127], "red");
+        assert_eq!(rgb_to_hsl([0, 255, 0]), [85, 255, 127], "green");
+        assert_eq!(rgb_to_hsl([0, 0, 0]), [0, 0, 0], "black");
+        assert_eq!(rgb_to_hsl([128, 128, 128]), [0, 0, 128], "grey");
+        //the saturation divisor is 0 for white
+        assert_eq!(rgb_to_hsl([255, 255, 255]), [0, 0, 255], "white");
+    }
+
+    #[test]
+    fn hsi() {
+        assert_eq!(rgb_to_hsi([255, 0, 0]), [0, 255, 85], "red");
+        assert_eq!(rgb_to_hsi([0, 0, 255]), [170, 255, 85], "blue");
+        assert_eq!(rgb_to_hsi([0, 0, 0]), [0, 0, 0], "black");
+        assert_eq!(rgb_to_hsi([128, 128, 128]), [0, 0, 128], "grey");
+        assert_eq!(rgb_to_hsi([255, 255, 255]), [0, 0, 255], "white");
+    }
+
+    #[test]
+    fn white_extraction() {
+        assert_eq!(extract_white([255, 255, 255]), (255, [0, 0, 0]), "white");
+        assert_eq!(extract_white([200, 100, 50]), (50, [150, 50, 0]), "mixed");
+        assert_eq!(extract_white([255, 128, 0]), (0, [255, 128, 0]), "no white part");
+    }
+
+    #[test]
+    fn amber_extraction() {
+        assert_eq!(extract_amber(AMBER), (255, [0, 0, 0]), "amber");
+        assert_eq!(extract_amber([255, 255, 255]), (255, [0, 64, 255]), "white");
+        assert_eq!(extract_amber([0, 0, 255]), (0, [0, 0, 255]), "blue has no amber part");
+        //rounding down never takes more than there is
+        assert_eq!(extract_amber([1, 1, 0]), (1, [0, 1, 0]), "smallest amber");
+    }
+
+    #[test]
+    fn channel_values() {
+        assert_eq!(
+            color_channel_values([255, 255, 255], &[RED, GREEN, BLUE, WHITE]),
+            vec![Some(0), Some(0), Some(0), Some(255)],
+            "white is produced by the white emitter",
+        );
+        assert_eq!(
+            color_channel_values([255, 255, 255], &[WHITE]),
+            vec![Some(255)],
+            "white only fixture",
+        );
+        assert_eq!(
+            color_channel_values([255, 0, 0], &[Color::Hsv(ColorHSV::Hue), Color::Hsv(ColorHSV::Saturation), Color::Hsv(ColorHSV::Value), UV]),
+            vec![Some(0), Some(255), Some(255), None],
+            "hsv fixture with uv",
+        );
+    }
+
+    #[test]
+    fn channel_values_round_trip() {
+        let roles = [RED, GREEN, BLUE, WHITE, AMBER_ROLE];
+        for rgb in [[255, 255, 255], [200, 100, 50], [0, 0, 0], [255, 191, 0], [17, 250, 3]] {
+            let channels = roles.iter()
+                .zip(color_channel_values(rgb, &roles))
+                .filter_map(|(role, value)|value.map(|value|(*role, value)))
+                .collect::<Vec<_>>();
+            assert_eq!(rgb_from_channel_values(&channels), Some(rgb), "round trip of {rgb:?}");
+        }
+        assert_eq!(rgb_from_channel_values(&[(WHITE, 255)]), None, "white only cannot be reversed");
+    }
+}
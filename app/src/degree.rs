@@ -7,6 +7,27 @@ pub const fn deg_to_microarcseconds(deg:u32) -> u64 {
         *1000 //to microarcsecond
 }
 
+///Converts micro-arc-seconds to whole degrees (rounded down).
+#[inline]
+pub const fn microarcseconds_to_deg(microarcseconds: u64) -> u64 {
+    microarcseconds / deg_to_microarcseconds(1)
+}
+
+///Formats micro-arc-seconds as degrees with up to two decimal places (e.g. `540°` or `262.5°`).
+pub fn format_deg(microarcseconds: u64) -> String {
+    let one_degree = deg_to_microarcseconds(1);
+    let degrees = microarcseconds / one_degree;
+    let hundredths = microarcseconds % one_degree * 100 / one_degree;
+    if hundredths == 0 {
+        format!("{degrees}°")
+    } else if hundredths % 10 == 0 {
+        let tenths = hundredths / 10;
+        format!("{degrees}.{tenths}°")
+    } else {
+        format!("{degrees}.{hundredths:02}°")
+    }
+}
+
 pub const fn scale_deg(input: u64, input_range: u64, output_range:u64) -> u64 {
     #[allow(clippy::cast_possible_truncation)]
     {
@@ -1,26 +1,129 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use once_cell::sync::Lazy;
 use serde_derive::{Deserialize, Serialize};
-use crate::artnet::fixture::channel::{Channel, Action, Color, ColorRGB, Range, SimpleAction};
+use crate::artnet::fixture::channel::{Channel, Action, Color, ColorRGB, Control, Laser, Range, SimpleAction, Slot};
 use crate::artnet::fixture::Fixture;
+use crate::artnet::fixture::validate::{validate_fixture, Lint};
 use crate::artnet::fixture::variables::{Variable, VariableChannelAction};
 use crate::degree::deg_to_microarcseconds;
 
+pub mod bundle;
+pub mod search;
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct FixtureStore{
     fixtures: Vec<Fixture>,
     contained_paths: HashMap<Arc<str>, FixtureStore>,
+    ///Changes, whenever fixtures are imported. Unique across all stores.
+    #[serde(skip)]
+    generation: u64,
+}
+
+///Returns a new generation for [`FixtureStore::generation`]. 0 is never returned, so that it only belongs to stores, that were never changed.
+fn next_generation() -> u64 {
+    static GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
 }
 
 impl FixtureStore{
 
     pub(crate) fn populate_fixture_store_defaults(&mut self){
         for fixture in [&VRSL_PAR_LIGHT, &VRSL_BAR_LIGHT, &VRSL_BLINDER, &VRSL_MOVING_HEAD, &VRSL_LASER]{
-            self.put_path(fixture.get_path().as_ref(), (*fixture).clone());
+            let mut fixture = (*fixture).clone();
+            //keep the response curve, that the user configured for a built-in fixture
+            if let Some(existing) = self.find(fixture.get_path().as_ref(), fixture.get_model()) {
+                fixture = fixture.with_response_curve(existing.get_response_curve().clone());
+            }
+            let model = fixture.get_model().clone();
+            for lint in self.import(fixture) {
+                log::warn!("Built-in fixture {}: {}: {}", model, lint.severity(), lint);
+            }
+        }
+    }
+
+    ///Validates `fixture` and puts it into the store at it's path.
+    ///A fixture with the same model at the same path gets replaced.
+    ///If the replaced fixture differs, the revision of `fixture` will be at least one higher than the replaced one.
+    ///If it only differs in the revision, the existing fixture is kept as is.
+    ///
+    ///Returns the problems found in the fixture definition.
+    pub fn import(&mut self, mut fixture: Fixture) -> Vec<Lint> {
+        let lints = validate_fixture(&fixture);
+        self.generation = next_generation();
+        self.get_path(fixture.get_path().as_ref(), |fs|{
+            match fs.fixtures.iter_mut().find(|x|x.get_model() == fixture.get_model()) {
+                Some(existing) => {
+                    if existing.clone().with_revision(0) == fixture.clone().with_revision(0) {
+                        return;
+                    }
+                    if fixture.get_revision() <= existing.get_revision() {
+                        fixture = fixture.with_revision(existing.get_revision().saturating_add(1));
+                    }
+                    *existing = fixture;
+                },
+                None => fs.fixtures.push(fixture),
+            }
+        });
+        lints
+    }
+
+    ///Identifies the current content of this store.
+    ///If two generations of a store are equal, the store has not been changed in between.
+    #[inline]
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    ///True, if there is a fixture with the model `model` at `path`.
+    pub fn contains(&self, path: &[Arc<str>], model: &Arc<str>) -> bool {
+        self.find(path, model).is_some()
+    }
+
+    ///Returns the fixture with the model `model` at `path`.
+    pub fn find(&self, path: &[Arc<str>], model: &Arc<str>) -> Option<&Fixture> {
+        match path.split_first() {
+            None => self.fixtures.iter().find(|fixture|fixture.get_model() == model),
+            Some((first, tail)) => self.contained_paths.get(first)?.find(tail, model),
+        }
+    }
+
+    ///Calls `func` with the path and the fixture for every fixture in this store.
+    pub fn for_each_fixture(&self, mut func: impl FnMut(&[Arc<str>], &Fixture)) {
+        self._for_each_fixture(&mut Vec::new(), &mut func);
+    }
+    fn _for_each_fixture(&self, path: &mut Vec<Arc<str>>, func: &mut impl FnMut(&[Arc<str>], &Fixture)) {
+        for fixture in &self.fixtures {
+            func(path.as_slice(), fixture);
+        }
+        let mut items = self.contained_paths.iter().collect::<Vec<_>>();
+        items.sort_by_key(|x|x.0);
+        for (key, value) in items {
+            path.push(key.clone());
+            value._for_each_fixture(path, func);
+            path.pop();
         }
     }
 
+    ///Validates every fixture in this store.
+    ///Returns the found problems together with the path of the affected fixture.
+    pub fn lint(&self) -> Vec<(String, Lint)> {
+        let mut seen = HashSet::new();
+        let mut lints = Vec::new();
+        self.for_each_fixture(|path, fixture|{
+            let mut fixture_path = String::new();
+            for segment in path.iter().chain(core::iter::once(fixture.get_model())) {
+                fixture_path.push('/');
+                fixture_path.push_str(segment.as_ref());
+            }
+            if !seen.insert(fixture_path.clone()) {
+                lints.push((fixture_path.clone(), Lint::DuplicatePath(fixture_path.clone())));
+            }
+            lints.extend(validate_fixture(fixture).into_iter().map(|lint|(fixture_path.clone(), lint)));
+        });
+        lints
+    }
+
     pub fn is_empty(&self) -> bool {
         self.fixtures.is_empty() && self.contained_paths.is_empty()
     }
@@ -43,10 +146,6 @@ impl FixtureStore{
         }
     }
 
-    fn put_path(&mut self, path: &[Arc<str>], fixture: Fixture) {
-        self.get_path(path, |fs|fs.fixtures.push(fixture));
-    }
-
     #[allow(clippy::significant_drop_tightening, clippy::significant_drop_in_scrutinee)]//false positive for items
     fn add_contained_fixtures(&self, ui: &mut egui::Ui,path: &mut Vec<Arc<str>>, item: &mut (Vec<Arc<str>>, Option<Fixture>)) {
         let mut items = self.fixtures.iter().collect::<Vec<_>>();
@@ -103,22 +202,23 @@ static VRSL_PANS:Lazy<Arc<[u64]>> = Lazy::new(||Arc::from([
     deg_to_microarcseconds(360),
     deg_to_microarcseconds(540)
 ]));
+const VRSL_DEFAULT_ANGLE:u64 = deg_to_microarcseconds(180);
 static VRSL_TILTS:Lazy<Arc<[u64]>> = Lazy::new(||Arc::from([
     deg_to_microarcseconds(180),
     deg_to_microarcseconds(250),
     deg_to_microarcseconds(270)
 ]));
+const VRSL_GOBO_COLOR:[u8;3] = [u8::MAX; 3];
 static VRSL_MOVING_HEAD:Lazy<Fixture> = Lazy::new(||Fixture::new(
     VRSL.clone(),
     STANDARD_MOVER_SPOTLIGHT.clone(),
     MOVING_HEAD.clone(),
     Arc::new([
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(Variable::Selection(VRSL_PANS.clone(), 180)))),
-        //todo: vrsl - currently disabled, because the linear smoothing algorithm outweighs this
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPanFine(Variable::Set(0)))),
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionTilt(Variable::Selection(VRSL_TILTS.clone(), 180)))),
-        //todo: vrsl - currently disabled, because the linear smoothing algorithm outweighs this
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionTiltFine(Variable::Set(0)))),
+        //note: vrsl - the linear smoothing algorithm mostly outweighs the fine channels
+        Channel::new_coarse(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(Variable::Selection(VRSL_PANS.clone(), VRSL_DEFAULT_ANGLE))), 1),
+        Channel::new_fine(0),
+        Channel::new_coarse(SimpleAction::VariableChannelAction(VariableChannelAction::PositionTilt(Variable::Selection(VRSL_TILTS.clone(), VRSL_DEFAULT_ANGLE))), 3),
+        Channel::new_fine(2),
         Channel::new_simple(SimpleAction::BeamZoom),
         Channel::new_simple(SimpleAction::IntensityMasterDimmer),
         Channel::new(Action::Selection(Arc::new([
@@ -133,16 +233,17 @@ static VRSL_MOVING_HEAD:Lazy<Fixture> = Lazy::new(||Fixture::new(
             Range::new(10, 126, SimpleAction::SpinLeft),
             Range::new(127, 255, SimpleAction::SpinRight),
         ]))),
+        //the gobos don't filter the light, so the swatch is white
         Channel::new(Action::Selection(Arc::new([
-            Range::new(0, 42, SimpleAction::GOBOSelection),
-            Range::new(43, 85, SimpleAction::GOBOSelection),
-            Range::new(86, 127, SimpleAction::GOBOSelection),
-            Range::new(128, 212, SimpleAction::GOBOSelection),
-            Range::new(213, 255, SimpleAction::GOBOSelection),
+            Range::new(0, 42, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from("GOBO 1")).with_color(VRSL_GOBO_COLOR)),
+            Range::new(43, 85, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from("GOBO 2")).with_color(VRSL_GOBO_COLOR)),
+            Range::new(86, 127, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from("GOBO 3")).with_color(VRSL_GOBO_COLOR)),
+            Range::new(128, 212, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from("GOBO 4")).with_color(VRSL_GOBO_COLOR)),
+            Range::new(213, 255, SimpleAction::GOBOSelection).with_slot(Slot::new(Arc::from("GOBO 5")).with_color(VRSL_GOBO_COLOR)),
         ]))),
-        Channel::new_simple(SimpleAction::Speed),
+        Channel::new_simple(SimpleAction::Control(Control::MovementSpeed)),
     ]),
-));
+).with_revision(2));
 static VRSL_PAR_LIGHT:Lazy<Fixture> = Lazy::new(||Fixture::new(
     VRSL.clone(),
     Arc::from("Standard Par Light"),
@@ -217,19 +318,150 @@ static VRSL_LASER:Lazy<Fixture> = Lazy::new(||Fixture::new(
     STANDARD_LASER.clone(),
     LASER.clone(),
     Arc::new([
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(Variable::Selection(VRSL_PANS.clone(), 180)))),
-        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionTilt(Variable::Selection(VRSL_TILTS.clone(), 180)))),
-        Channel::new_simple(SimpleAction::NoOp), //todo: laser width
-        Channel::new_simple(SimpleAction::NoOp), //todo: laser flatness
-        Channel::new_simple(SimpleAction::NoOp), //todo: beam count
+        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionPan(Variable::Selection(VRSL_PANS.clone(), VRSL_DEFAULT_ANGLE)))),
+        Channel::new_simple(SimpleAction::VariableChannelAction(VariableChannelAction::PositionTilt(Variable::Selection(VRSL_TILTS.clone(), VRSL_DEFAULT_ANGLE)))),
+        Channel::new_simple(SimpleAction::Laser(Laser::Width)),
+        Channel::new_simple(SimpleAction::Laser(Laser::Flatness)),
+        Channel::new_simple(SimpleAction::Laser(Laser::BeamCount)),
         Channel::new_simple(SimpleAction::SpinLeft), //todo: spin left or right?
         Channel::new_simple(SimpleAction::IntensityMasterDimmer),
         Channel::new_simple(SimpleAction::IntensityColor(Color::Rgb(ColorRGB::Red))),
         Channel::new_simple(SimpleAction::IntensityColor(Color::Rgb(ColorRGB::Green))),
         Channel::new_simple(SimpleAction::IntensityColor(Color::Rgb(ColorRGB::Blue))),
-        Channel::new_simple(SimpleAction::NoOp), //todo: beam thickness
-        Channel::new_simple(SimpleAction::NoOp), //todo: length
-        Channel::new_simple(SimpleAction::Speed),
+        Channel::new_simple(SimpleAction::Laser(Laser::Thickness)),
+        Channel::new_simple(SimpleAction::Laser(Laser::Length)),
+        Channel::new_simple(SimpleAction::Control(Control::MovementSpeed)),
     ]),
-));
-//</editor-fold>
\ No newline at end of file
+).with_revision(2));
+//</editor-fold>
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artnet::fixture::curve::ResponseCurve;
+    use crate::artnet::fixture::validate::Severity;
+    use crate::fixturestore::bundle::{ConflictResolution, FixtureBundle};
+    use crate::fixturestore::search::SearchIndex;
+
+    fn revision_in(store: &FixtureStore, fixture: &Fixture) -> Option<u32> {
+        store.find(fixture.get_path().as_ref(), fixture.get_model()).map(Fixture::get_revision)
+    }
+
+    #[test]
+    fn same_content_with_lower_revision_is_no_change() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone().with_revision(2));
+        store.import(VRSL_PAR_LIGHT.clone().with_revision(1));
+        assert_eq!(revision_in(&store, &VRSL_PAR_LIGHT), Some(2), "an unchanged fixture must keep its revision");
+        store.populate_fixture_store_defaults();
+        assert_eq!(revision_in(&store, &VRSL_PAR_LIGHT), Some(2), "re-populating the defaults must not bump the revision");
+    }
+
+    #[test]
+    fn changed_content_bumps_revision() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone().with_revision(2));
+        store.import(VRSL_PAR_LIGHT.clone().with_revision(1).with_response_curve(ResponseCurve::Square));
+        assert_eq!(revision_in(&store, &VRSL_PAR_LIGHT), Some(3), "a changed fixture must get a higher revision");
+    }
+
+    #[test]
+    fn built_in_fixtures_have_no_errors() {
+        for fixture in [&VRSL_PAR_LIGHT, &VRSL_BAR_LIGHT, &VRSL_BLINDER, &VRSL_MOVING_HEAD, &VRSL_LASER] {
+            let errors = validate_fixture(fixture).into_iter()
+                .filter(|lint|lint.severity() == Severity::Error)
+                .collect::<Vec<_>>();
+            assert!(errors.is_empty(), "{} must not have errors, got {errors:?}", fixture.get_model());
+        }
+    }
+
+    fn exported_models(store: &FixtureStore, path: &[&str]) -> Vec<Arc<str>> {
+        let path = path.iter().map(|segment|Arc::from(*segment)).collect::<Vec<_>>();
+        let mut models = store.export(path.as_slice()).get_fixtures().iter()
+            .map(|fixture|fixture.get_model().clone())
+            .collect::<Vec<_>>();
+        models.sort();
+        models
+    }
+
+    #[test]
+    fn export_selects_by_path_prefix() {
+        let mut store = FixtureStore::default();
+        store.populate_fixture_store_defaults();
+        let all = exported_models(&store, &[]);
+        assert_eq!(all.len(), 5, "an empty path must export the whole store, got {all:?}");
+        assert_eq!(exported_models(&store, &["VRSL"]), all, "a directory must export all fixtures below it");
+        assert_eq!(
+            exported_models(&store, &["VRSL", "Standard Par Light"]),
+            vec![Arc::<str>::from("Standard Par Light")],
+            "a model as last segment must export only that fixture"
+        );
+        assert!(exported_models(&store, &["VRSL", "Standard"]).is_empty(), "segments must match completely, not only their start");
+        assert!(exported_models(&store, &["Other"]).is_empty(), "an unknown directory must export nothing");
+    }
+
+    fn par_light_bundle() -> FixtureBundle {
+        FixtureBundle::new(vec![VRSL_PAR_LIGHT.clone().with_response_curve(ResponseCurve::Square)])
+    }
+
+    fn par_light_curve(store: &FixtureStore, model: &str) -> Option<ResponseCurve> {
+        store.find(VRSL_PAR_LIGHT.get_path().as_ref(), &Arc::from(model)).map(|fixture|fixture.get_response_curve().clone())
+    }
+
+    #[test]
+    fn import_conflict_skip() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone());
+        let report = store.import_bundle(par_light_bundle(), ConflictResolution::Skip);
+        assert_eq!((report.added, report.overwritten, report.skipped), (0, 0, 1), "the existing fixture must be skipped");
+        assert_eq!(par_light_curve(&store, "Standard Par Light"), Some(ResponseCurve::Linear), "a skipped fixture must not be changed");
+    }
+
+    #[test]
+    fn import_conflict_overwrite() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone());
+        let report = store.import_bundle(par_light_bundle(), ConflictResolution::Overwrite);
+        assert_eq!((report.added, report.overwritten, report.skipped), (0, 1, 0), "the existing fixture must be overwritten");
+        assert_eq!(par_light_curve(&store, "Standard Par Light"), Some(ResponseCurve::Square), "the imported fixture must replace the existing one");
+    }
+
+    #[test]
+    fn import_conflict_keep_both() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone());
+        let report = store.import_bundle(par_light_bundle(), ConflictResolution::KeepBoth);
+        assert_eq!(
+            report.renamed,
+            vec![(Arc::<str>::from("Standard Par Light"), Arc::<str>::from("Standard Par Light (2)"))],
+            "the first copy must get the suffix (2)"
+        );
+        let report = store.import_bundle(par_light_bundle(), ConflictResolution::KeepBoth);
+        assert_eq!(
+            report.renamed,
+            vec![(Arc::<str>::from("Standard Par Light"), Arc::<str>::from("Standard Par Light (3)"))],
+            "the suffix must be increased until the model is unused"
+        );
+        assert_eq!(par_light_curve(&store, "Standard Par Light"), Some(ResponseCurve::Linear), "the existing fixture must be kept");
+        assert_eq!(par_light_curve(&store, "Standard Par Light (2)"), Some(ResponseCurve::Square), "the first copy must be imported");
+        assert_eq!(par_light_curve(&store, "Standard Par Light (3)"), Some(ResponseCurve::Square), "the second copy must be imported");
+    }
+
+    #[test]
+    fn import_without_conflict_adds() {
+        let mut store = FixtureStore::default();
+        let report = store.import_bundle(par_light_bundle(), ConflictResolution::Skip);
+        assert_eq!((report.added, report.overwritten, report.skipped), (1, 0, 0), "a new fixture must always be added");
+        assert_eq!(par_light_curve(&store, "Standard Par Light"), Some(ResponseCurve::Square), "the new fixture must be in the store");
+    }
+
+    #[test]
+    fn search_index_is_rebuilt_after_import() {
+        let mut store = FixtureStore::default();
+        store.import(VRSL_PAR_LIGHT.clone());
+        let mut index = None;
+        assert_eq!(SearchIndex::refresh(&mut index, &store).fixture_count(), 1, "the index must contain the imported fixture");
+        store.import(VRSL_LASER.clone());
+        assert_eq!(SearchIndex::refresh(&mut index, &store).fixture_count(), 2, "the index must be rebuilt after the store changed");
+    }
+}
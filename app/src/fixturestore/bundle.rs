@@ -0,0 +1,140 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use serde_derive::{Deserialize, Serialize};
+use crate::artnet::fixture::Fixture;
+use crate::artnet::fixture::validate::Lint;
+use crate::fixturestore::FixtureStore;
+
+///The bundle format version, that this version of the app writes.
+const BUNDLE_VERSION: u32 = 1;
+
+///A portable collection of fixtures, e.g. for exchanging custom fixtures with other people.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FixtureBundle{
+    version: u32,
+    fixtures: Vec<Fixture>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError{
+    #[error("The bundle could not be read or written: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The bundle could not be parsed: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("The bundle could not be serialized: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("The bundle has the version {0}, which is newer than the supported version {BUNDLE_VERSION}.")]
+    UnsupportedVersion(u32),
+}
+
+///What to do, when an imported fixture has the same path and model as a fixture already in the store.
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ConflictResolution{
+    #[default]
+    Skip,
+    Overwrite,
+    ///Import the fixture with a numbered suffix added to it's model name.
+    KeepBoth,
+}
+
+impl Display for ConflictResolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "Skip"),
+            Self::Overwrite => write!(f, "Overwrite"),
+            Self::KeepBoth => write!(f, "Keep Both"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport{
+    pub added: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+    ///Fixtures, that were imported under a new model name
+    pub renamed: Vec<(Arc<str>, Arc<str>)>,
+    ///Problems in the imported fixtures
+    pub lints: Vec<(Arc<str>, Lint)>,
+}
+
+impl FixtureBundle {
+    pub fn new(fixtures: Vec<Fixture>) -> Self {
+        Self{
+            version: BUNDLE_VERSION,
+            fixtures,
+        }
+    }
+
+    #[inline]
+    pub fn get_fixtures(&self) -> &[Fixture] {
+        self.fixtures.as_slice()
+    }
+
+    pub fn to_ron(&self) -> Result<String, BundleError> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+
+    pub fn from_ron(data: &str) -> Result<Self, BundleError> {
+        let bundle:Self = ron::de::from_str(data)?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(bundle.version));
+        }
+        Ok(bundle)
+    }
+}
+
+impl FixtureStore {
+    ///Collects all fixtures below `path` into a bundle.
+    ///
+    ///`path` may either point to a directory of the store (e.g. `VRSL`)
+    /// or to a single fixture, in which case the last segment is the model (e.g. `VRSL/Standard Laser`).
+    ///An empty path exports the whole store.
+    pub fn export(&self, path: &[Arc<str>]) -> FixtureBundle {
+        let mut fixtures = Vec::new();
+        self.for_each_fixture(|fixture_path, fixture|{
+            let full_path = fixture_path.iter().chain(core::iter::once(fixture.get_model()));
+            if full_path.take(path.len()).eq(path.iter()) {
+                fixtures.push(fixture.clone());
+            }
+        });
+        FixtureBundle::new(fixtures)
+    }
+
+    ///Imports all fixtures from `bundle`.
+    ///Fixtures, that have the same path and model as an existing fixture get handled according to `resolution`.
+    pub fn import_bundle(&mut self, bundle: FixtureBundle, resolution: ConflictResolution) -> ImportReport {
+        let mut report = ImportReport::default();
+        for fixture in bundle.fixtures {
+            let path = fixture.get_path();
+            let exists = self.contains(path.as_ref(), fixture.get_model());
+            let fixture = match (exists, resolution) {
+                (false, _) => {
+                    report.added += 1;
+                    fixture
+                },
+                (true, ConflictResolution::Skip) => {
+                    report.skipped += 1;
+                    continue;
+                },
+                (true, ConflictResolution::Overwrite) => {
+                    report.overwritten += 1;
+                    fixture
+                },
+                (true, ConflictResolution::KeepBoth) => {
+                    let mut suffix = 2u32;
+                    let mut model = Arc::from(format!("{} ({suffix})", fixture.get_model()));
+                    while self.contains(path.as_ref(), &model) {
+                        suffix += 1;
+                        model = Arc::from(format!("{} ({suffix})", fixture.get_model()));
+                    }
+                    report.renamed.push((fixture.get_model().clone(), model.clone()));
+                    fixture.with_model(model)
+                },
+            };
+            let model = fixture.get_model().clone();
+            report.lints.extend(self.import(fixture).into_iter().map(|lint|(model.clone(), lint)));
+        }
+        report
+    }
+}
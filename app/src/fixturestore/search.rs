@@ -0,0 +1,147 @@
+use std::sync::Arc;
+use crate::artnet::fixture::Fixture;
+use crate::fixturestore::FixtureStore;
+
+///Bonus for a query token, that is a substring of a field.
+const SUBSTRING_SCORE: u32 = 1000;
+///Additional bonus, if that substring starts at a word boundary.
+const WORD_START_SCORE: u32 = 500;
+///Base score for a query token, whose chars only appear in order (but not next to each other) in a field.
+const SUBSEQUENCE_SCORE: u32 = 500;
+///Penalty for every char, that has to be skipped when matching a subsequence.
+const GAP_PENALTY: u32 = 10;
+
+///A searchable snapshot of all fixtures in a [`FixtureStore`].
+#[derive(Debug, Default, Clone)]
+pub struct SearchIndex{
+    entries: Vec<SearchEntry>,
+    ///The [`FixtureStore::generation`] this index was built from.
+    generation: u64,
+}
+
+#[derive(Debug, Clone)]
+struct SearchEntry{
+    ///Path of the fixture in the fixture store, including the model.
+    path: Vec<Arc<str>>,
+    fixture: Fixture,
+    ///lowercase manufacturer, model, type and path
+    fields: [String; 4],
+    ///The channel counts of all modes of the fixture (e.g. `13` and `13ch`)
+    channel_counts: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SearchResult<'a>{
+    pub path: &'a [Arc<str>],
+    pub fixture: &'a Fixture,
+    pub score: u32,
+}
+
+impl SearchIndex {
+    pub fn new(fixture_store: &FixtureStore) -> Self {
+        crate::profile_scope!("SearchIndex::new");
+        let mut entries = Vec::new();
+        fixture_store.for_each_fixture(|path, fixture|{
+            let mut full_path = path.to_vec();
+            full_path.push(fixture.get_model().clone());
+            let path_string = full_path.iter()
+                .map(|segment|segment.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("/");
+            let channel_counts = fixture.get_modes().iter()
+                .flat_map(|mode|{
+                    let count = mode.get_channels().len();
+                    [count.to_string(), format!("{count}ch")]
+                })
+                .collect();
+            entries.push(SearchEntry{
+                fields: [
+                    fixture.get_manufacturer().to_lowercase(),
+                    fixture.get_model().to_lowercase(),
+                    fixture.get_type().to_lowercase(),
+                    path_string,
+                ],
+                path: full_path,
+                fixture: fixture.clone(),
+                channel_counts,
+            });
+        });
+        Self{
+            entries,
+            generation: fixture_store.generation(),
+        }
+    }
+
+    ///Returns the index in `index`. It gets (re)built, if there is none yet or `fixture_store` changed since it was built.
+    pub fn refresh<'a>(index: &'a mut Option<Self>, fixture_store: &FixtureStore) -> &'a Self {
+        if !matches!(index, Some(index) if index.generation == fixture_store.generation()) {
+            *index = None;
+        }
+        index.get_or_insert_with(||Self::new(fixture_store))
+    }
+
+    ///The number of fixtures in this index.
+    pub fn fixture_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    ///Fuzzy searches for fixtures matching all whitespace separated tokens of `query`.
+    ///The results are ordered by descending relevance.
+    ///An empty query returns all fixtures, ordered by their path.
+    pub fn search(&self, query: &str) -> Vec<SearchResult<'_>> {
+        crate::profile_scope!("SearchIndex::search");
+        let query = query.to_lowercase();
+        let tokens = query.split_whitespace().collect::<Vec<_>>();
+        let mut results = self.entries.iter()
+            .filter_map(|entry|{
+                let mut score = 0u32;
+                for token in &tokens {
+                    let token_score = entry.fields.iter()
+                        .filter_map(|field|fuzzy_score(token, field))
+                        .chain(
+                            entry.channel_counts.iter()
+                                .filter(|count|count.as_str() == *token)
+                                .map(|_|SUBSTRING_SCORE + WORD_START_SCORE)
+                        )
+                        .max()?;
+                    score = score.saturating_add(token_score);
+                }
+                Some(SearchResult{
+                    path: entry.path.as_slice(),
+                    fixture: &entry.fixture,
+                    score,
+                })
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b|b.score.cmp(&a.score).then_with(||a.path.cmp(b.path)));
+        results
+    }
+}
+
+///Scores how well `needle` matches `haystack`. Both should be lowercase.
+///
+///Substrings score the highest, especially if they start at the beginning of a word.
+///Otherwise all chars of `needle` need to appear in order in `haystack`,
+///where every skipped char reduces the score.
+///Returns `None`, if `needle` doesn't match at all.
+fn fuzzy_score(needle: &str, haystack: &str) -> Option<u32> {
+    if let Some(pos) = haystack.find(needle) {
+        let word_start = haystack.get(..pos)
+            .map_or(true, |before|before.is_empty() || before.ends_with(|c:char|!c.is_alphanumeric()));
+        let position_penalty = u32::try_from(pos).unwrap_or(u32::MAX).min(SUBSTRING_SCORE / 2);
+        return Some(SUBSTRING_SCORE + if word_start {WORD_START_SCORE} else {0} - position_penalty);
+    }
+
+    let mut haystack = haystack.chars();
+    let mut gaps = 0u32;
+    for char in needle.chars() {
+        loop {
+            match haystack.next() {
+                Some(c) if c == char => break,
+                Some(_) => gaps = gaps.saturating_add(1),
+                None => return None,
+            }
+        }
+    }
+    Some(SUBSEQUENCE_SCORE.saturating_sub(gaps.saturating_mul(GAP_PENALTY)))
+}
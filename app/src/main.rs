@@ -43,6 +43,9 @@
 #![allow(clippy::semicolon_if_nothing_returned, clippy::module_name_repetitions)]
 #![windows_subsystem = "windows"]
 
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
 use std::sync::OnceLock;
 use log::LevelFilter;
 use tokio::runtime::{Builder, Runtime};
@@ -55,6 +58,7 @@ mod app;
 mod artnet;
 mod fixturestore;
 mod degree;
+mod color;
 mod r#macro;
 pub(crate) use r#macro::profile_scope;
 
@@ -88,9 +92,43 @@ fn init_logging() {
     println!("Not setting up any logger.");
 }
 
-fn main() {
+///Validates the fixtures of the project at `project` and prints all found problems.
+///Without a project only the built-in fixtures get validated.
+fn lint_fixtures(project: Option<PathBuf>) -> ExitCode {
+    let fixture_store = match project {
+        Some(project) => match app::App::load_fixture_store(Arc::from(project.as_path())) {
+            Ok(fixture_store) => fixture_store,
+            Err(err) => {
+                eprintln!("Unable to load the fixtures of the project {}: {err}", project.display());
+                return ExitCode::FAILURE;
+            },
+        },
+        None => {
+            let mut fixture_store = fixturestore::FixtureStore::default();
+            fixture_store.populate_fixture_store_defaults();
+            fixture_store
+        },
+    };
+    let lints = fixture_store.lint();
+    for (path, lint) in &lints {
+        println!("{}: {path}: {lint}", lint.severity());
+    }
+    println!("Found {} problem(s).", lints.len());
+    if lints.iter().any(|(_, lint)|lint.severity() == artnet::fixture::validate::Severity::Error) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn main() -> ExitCode {
     init_logging();
     log::info!("Logger initialized");
+    //usage: --lint-fixtures [project file]
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(index) = args.iter().position(|arg|arg == "--lint-fixtures") {
+        return lint_fixtures(args.get(index + 1).map(PathBuf::from));
+    }
     let rt = get_runtime();
     let _a = rt.enter(); // "_" as a variable name immediately drops the value, causing no tokio runtime to be registered. "_a" does not.
     log::info!("Tokio Runtime initialized");
@@ -110,4 +148,5 @@ fn main() {
         );
     }
     println!("GUI exited. Thank you for using {APP_NAME}!");
+    ExitCode::SUCCESS
 }